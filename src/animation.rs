@@ -1,5 +1,7 @@
+use std::rc::Rc;
+
 use graphics::ImageSize;
-use graphics::math::Scalar;
+use graphics::math::{ Scalar, Vec2d };
 
 use ai_behavior::{
     Status,
@@ -8,7 +10,9 @@ use ai_behavior::{
 };
 
 use interpolation::EaseFunction;
-use sprite::Sprite;
+use clip::Clip;
+use path::{ Path, SplineTiming };
+use sprite::{ AnimatedProperties, Content, Sprite };
 
 /// Animations supported by Sprite
 #[derive(Clone, PartialEq)]
@@ -61,13 +65,187 @@ pub enum Animation {
     ///
     /// Set the sprite's opacity to specified value in `dt` seconds
     FadeTo(f64, f64),
+    /// duration, r, g, b
+    ///
+    /// Tint the sprite to specified color in `dt` seconds
+    TintTo(f64, f64, f64, f64),
+    /// duration, r, g, b
+    ///
+    /// Tint the sprite to specified color in `dt` seconds, relatively
+    TintBy(f64, f64, f64, f64),
+    /// duration, deg
+    ///
+    /// Shift the hue of the sprite's color by specified degree in `dt` seconds
+    HueShift(f64, f64),
+    /// duration, saturation
+    ///
+    /// Set the saturation of the sprite's color to specified value in `dt` seconds
+    SaturateTo(f64, f64),
     /// ease_function, animation
     ///
     /// Tweening the animation with ease function
     Ease(EaseFunction, Box<Animation>),
+    /// trauma
+    ///
+    /// Add trauma to the scene's camera to shake the screen.
+    /// It happens at once, wrapping it in `Ease`, `Reverse` or `Yoyo`
+    /// has no effect
+    Shake(Scalar),
+    /// duration, amount
+    ///
+    /// Zoom the scene's camera in by `amount`, settling back in `dt` seconds.
+    /// It starts at once, wrapping it in `Ease`, `Reverse` or `Yoyo`
+    /// has no effect
+    ZoomPunch(f64, Scalar),
+    /// duration
+    ///
+    /// Reveal the characters of a bitmap text one by one in `dt` seconds
+    RevealText(f64),
+    /// count
+    ///
+    /// Spawn particles of an emitter at once
+    EmitBurst(usize),
+    /// duration, x, y, w, h
+    ///
+    /// Change the sprite's clip rectangle to specified rectangle in `dt` seconds,
+    /// starting from the whole content if it does not clip with a rectangle
+    ClipTo(f64, Scalar, Scalar, Scalar, Scalar),
+    /// vx, vy
+    ///
+    /// Set the sprite's velocity
+    SetVelocity(Scalar, Scalar),
+    /// dvx, dvy
+    ///
+    /// Add to the sprite's velocity
+    Impulse(Scalar, Scalar),
+    /// deg
+    ///
+    /// Set the sprite's angular velocity (in degree per second)
+    SetAngularVelocity(Scalar),
+    /// duration, path
+    ///
+    /// Move sprite along the path at a constant speed in `dt` seconds,
+    /// the path is relative to the sprite's position
+    MoveAlongPath(f64, Path),
+    /// duration, waypoints, closed, timing
+    ///
+    /// Move sprite through the waypoints along a centripetal Catmull-Rom
+    /// spline in `dt` seconds, starting from the first one
+    FollowSpline(f64, Vec<Vec2d>, bool, SplineTiming),
+    /// duration, x, y
+    ///
+    /// Change the sprite's anchor to specified anchor in `dt` seconds
+    AnchorTo(f64, Scalar, Scalar),
+    /// duration, x, y, w, h
+    ///
+    /// Change the sprite's source rectangle to specified rectangle in `dt` seconds,
+    /// starting from the whole texture if it has none
+    SrcRectTo(f64, Scalar, Scalar, Scalar, Scalar),
+    /// animation
+    ///
+    /// Play a tween backward, from where it would end to where it starts.
+    /// `Scene::run` panics if the animation is not a tween
+    Reverse(Box<Animation>),
+    /// animation, times
+    ///
+    /// Play a tween forward then backward, `times` times or forever if None.
+    /// It finishes at once without changing the sprite if `times` is 0.
+    /// `Scene::run` panics if the animation is not a tween
+    Yoyo(Box<Animation>, Option<usize>),
 }
 
 impl Animation {
+    /// Get how long the animation runs for, in seconds
+    ///
+    /// Instant animations and camera effects take no time.
+    pub fn duration(&self) -> Option<f64> {
+        use Animation::*;
+
+        match *self {
+            MoveTo(dur, ..) | MoveBy(dur, ..) |
+            RotateTo(dur, _) | RotateBy(dur, _) |
+            ScaleTo(dur, ..) | ScaleBy(dur, ..) |
+            Blink(dur, _) |
+            FadeIn(dur) | FadeOut(dur) | FadeTo(dur, _) |
+            TintTo(dur, ..) | TintBy(dur, ..) |
+            HueShift(dur, _) | SaturateTo(dur, _) |
+            RevealText(dur) | ClipTo(dur, ..) |
+            MoveAlongPath(dur, _) | FollowSpline(dur, ..) |
+            AnchorTo(dur, ..) | SrcRectTo(dur, ..) => Some(dur),
+            Ease(_, ref animation) | Reverse(ref animation) => animation.duration(),
+            Yoyo(ref animation, times) => {
+                times.and_then(|n| animation.duration().map(|d| d * 2.0 * n as f64))
+            },
+            FlipX(_) | FlipY(_) | Show | Hide | ToggleVisibility |
+            Shake(_) | ZoomPunch(..) | EmitBurst(_) |
+            SetVelocity(..) | Impulse(..) | SetAngularVelocity(_) => Some(0.0),
+        }
+    }
+
+    /// Whether or not the animation changes the sprite gradually over its
+    /// duration, so it can be played backward by `Reverse` and `Yoyo`
+    pub fn is_tween(&self) -> bool {
+        use Animation::*;
+
+        match *self {
+            MoveTo(..) | MoveBy(..) |
+            RotateTo(..) | RotateBy(..) |
+            ScaleTo(..) | ScaleBy(..) |
+            FadeIn(_) | FadeOut(_) | FadeTo(..) |
+            TintTo(..) | TintBy(..) |
+            HueShift(..) | SaturateTo(..) |
+            RevealText(_) | ClipTo(..) |
+            MoveAlongPath(..) | FollowSpline(..) |
+            AnchorTo(..) | SrcRectTo(..) => true,
+            Ease(_, ref animation) | Reverse(ref animation) | Yoyo(ref animation, _) => {
+                animation.is_tween()
+            },
+            FlipX(_) | FlipY(_) | Show | Hide | ToggleVisibility | Blink(..) |
+            Shake(_) | ZoomPunch(..) | EmitBurst(_) |
+            SetVelocity(..) | Impulse(..) | SetAngularVelocity(_) => false,
+        }
+    }
+
+    // Whether or not every `Reverse` and `Yoyo` in the animation wraps a tween
+    pub(crate) fn is_valid(&self) -> bool {
+        use Animation::*;
+
+        match *self {
+            Reverse(ref animation) | Yoyo(ref animation, _) => animation.is_tween(),
+            Ease(_, ref animation) => animation.is_valid(),
+            _ => true,
+        }
+    }
+
+    // Mark the properties of the sprite the animation changes
+    pub(crate) fn add_properties(&self, p: &mut AnimatedProperties) {
+        use Animation::*;
+
+        match *self {
+            MoveTo(..) | MoveBy(..) | FollowSpline(..) => p.position = true,
+            MoveAlongPath(_, ref path) => {
+                p.position = true;
+                p.rotation |= path.get_auto_rotate();
+            },
+            RotateTo(..) | RotateBy(..) => p.rotation = true,
+            ScaleTo(..) | ScaleBy(..) => p.scale = true,
+            FlipX(_) | FlipY(_) => p.flip = true,
+            Show | Hide | ToggleVisibility | Blink(..) => p.visible = true,
+            FadeIn(_) | FadeOut(_) | FadeTo(..) => p.opacity = true,
+            TintTo(..) | TintBy(..) | HueShift(..) | SaturateTo(..) => p.color = true,
+            RevealText(_) => p.visible_chars = true,
+            ClipTo(..) => p.clip = true,
+            SetVelocity(..) | Impulse(..) => p.velocity = true,
+            SetAngularVelocity(_) => p.angular_velocity = true,
+            AnchorTo(..) => p.anchor = true,
+            SrcRectTo(..) => p.src_rect = true,
+            Ease(_, ref animation) | Reverse(ref animation) | Yoyo(ref animation, _) => {
+                animation.add_properties(p)
+            },
+            Shake(_) | ZoomPunch(..) | EmitBurst(_) => {},
+        }
+    }
+
     /// Generate a new state from Animation with specified Sprite
     pub fn to_state<I: ImageSize>(&self, sprite: &Sprite<I>) -> AnimationState {
         use Animation::*;
@@ -131,9 +309,85 @@ impl Animation {
                 let b = sprite.get_opacity() as f64;
                 S::Fade(0.0, b, d - b, dur)
             },
+            TintTo(dur, r, g, b) => {
+                let begin = get_rgb(sprite);
+                S::Tint(0.0, begin, [r - begin[0], g - begin[1], b - begin[2]], dur)
+            },
+            TintBy(dur, r, g, b) => {
+                S::Tint(0.0, get_rgb(sprite), [r, g, b], dur)
+            },
+            HueShift(dur, deg) => {
+                S::Hsv(0.0, rgb_to_hsv(get_rgb(sprite)), [deg, 0.0, 0.0], dur)
+            },
+            SaturateTo(dur, s) => {
+                let begin = rgb_to_hsv(get_rgb(sprite));
+                S::Hsv(0.0, begin, [0.0, s - begin[1], 0.0], dur)
+            },
             Ease(f, ref animation) => {
                 S::Ease(f, Box::new(animation.to_state(sprite)))
             },
+            Shake(trauma) => {
+                S::Shake(trauma)
+            },
+            ZoomPunch(dur, amount) => {
+                S::ZoomPunch(dur, amount)
+            },
+            RevealText(dur) => {
+                let total = match *sprite.get_content() {
+                    Content::BitmapText(ref text) => text.char_count(),
+                    _ => 0,
+                };
+                S::Reveal(0.0, total as f64, dur)
+            },
+            EmitBurst(count) => {
+                S::Burst(count)
+            },
+            ClipTo(dur, x, y, w, h) => {
+                let begin = match sprite.get_clip() {
+                    Some(Clip::Rect(rect)) => rect,
+                    _ => {
+                        let (w, h) = sprite.content_size();
+                        [0.0, 0.0, w, h]
+                    }
+                };
+                let change = [x - begin[0], y - begin[1], w - begin[2], h - begin[3]];
+                S::ClipRect(0.0, begin, change, dur)
+            },
+            SetVelocity(vx, vy) => {
+                S::Velocity(vx, vy)
+            },
+            Impulse(dx, dy) => {
+                let (vx, vy) = sprite.get_velocity();
+                S::Velocity(vx + dx, vy + dy)
+            },
+            SetAngularVelocity(deg) => {
+                S::AngularVelocity(deg)
+            },
+            MoveAlongPath(dur, ref path) => {
+                let (x, y) = sprite.get_position();
+                S::FollowPath(0.0, [x, y], Rc::new(path.clone()), dur)
+            },
+            FollowSpline(dur, ref waypoints, closed, timing) => {
+                S::Spline(0.0, Rc::new(Path::catmull_rom(waypoints, closed)), timing, dur)
+            },
+            AnchorTo(dur, x, y) => {
+                let (bx, by) = sprite.get_anchor();
+                S::Anchor(0.0, [bx, by], [x - bx, y - by], dur)
+            },
+            SrcRectTo(dur, x, y, w, h) => {
+                let begin = sprite.get_src_rect().unwrap_or_else(|| {
+                    let (w, h) = sprite.content_size();
+                    [0.0, 0.0, w, h]
+                });
+                let change = [x - begin[0], y - begin[1], w - begin[2], h - begin[3]];
+                S::SrcRect(0.0, begin, change, dur)
+            },
+            Reverse(ref animation) => {
+                S::Reverse(Box::new(animation.to_state(sprite)))
+            },
+            Yoyo(ref animation, times) => {
+                S::Yoyo(Box::new(animation.to_state(sprite)), 0, times.map(|n| 2 * n))
+            },
         }
     }
 }
@@ -155,8 +409,46 @@ pub enum AnimationState {
     Blink(f64, f64, usize, usize),
     /// time, begin, change, duration
     Fade(f64, f64, f64, f64),
+    /// time, begin_rgb, change_rgb, duration
+    Tint(f64, [f64; 3], [f64; 3], f64),
+    /// time, begin_hsv, change_hsv, duration
+    ///
+    /// Hue is in degree, saturation and value from 0 to 1
+    Hsv(f64, [f64; 3], [f64; 3], f64),
     /// ease_function, animation
     Ease(EaseFunction, Box<AnimationState>),
+    /// trauma
+    ///
+    /// Applied by the scene's camera, it has no effect on the sprite
+    Shake(Scalar),
+    /// duration, amount
+    ///
+    /// Applied by the scene's camera, it has no effect on the sprite
+    ZoomPunch(f64, Scalar),
+    /// time, characters, duration
+    Reveal(f64, f64, f64),
+    /// count
+    Burst(usize),
+    /// time, begin_rect, change_rect, duration
+    ClipRect(f64, [Scalar; 4], [Scalar; 4], f64),
+    /// vx, vy
+    Velocity(Scalar, Scalar),
+    /// deg
+    AngularVelocity(Scalar),
+    /// time, begin, path, duration
+    FollowPath(f64, Vec2d, Rc<Path>, f64),
+    /// time, spline, timing, duration
+    Spline(f64, Rc<Path>, SplineTiming, f64),
+    /// time, begin_anchor, change_anchor, duration
+    Anchor(f64, Vec2d, Vec2d, f64),
+    /// time, begin_rect, change_rect, duration
+    SrcRect(f64, [Scalar; 4], [Scalar; 4], f64),
+    /// animation
+    Reverse(Box<AnimationState>),
+    /// animation, leg, legs
+    ///
+    /// Odd legs play backward, `legs` is None to play forever
+    Yoyo(Box<AnimationState>, usize, Option<usize>),
 }
 
 impl AnimationState {
@@ -190,19 +482,35 @@ impl AnimationState {
                 sprite.set_visible(visible);
                 (None, Success, dt)
             },
+            Shake(_) | ZoomPunch(_, _) => {
+                (None, Success, dt)
+            },
+            Velocity(vx, vy) => {
+                sprite.set_velocity(vx, vy);
+                (None, Success, dt)
+            },
+            AngularVelocity(deg) => {
+                sprite.set_angular_velocity(deg);
+                (None, Success, dt)
+            },
+            Burst(count) => {
+                if let Content::Emitter(ref mut emitter) = *sprite.get_content_mut() {
+                    emitter.burst(count);
+                }
+                (None, Success, dt)
+            },
             Blink(past, dur, cur, total) => {
+                // every toggle passed in `dt`, however long it is
                 let period = dur / total as f64;
-                if past + dt >= (cur + 1) as f64 * period {
+                let reached = (((past + dt) / period) as usize).clamp(cur, total);
+                if (reached - cur) % 2 == 1 {
                     let visible = sprite.get_visible();
                     sprite.set_visible(!visible);
-                    if past + dt >= dur {
-                        (None, Success, past + dt - dur)
-                    } else {
-                        (Some(Blink(past + dt, dur, cur + 1, total)),
-                         Running, 0.0)
-                    }
+                }
+                if past + dt >= dur {
+                    (None, Success, past + dt - dur)
                 } else {
-                    (Some(Blink(past + dt, dur, cur, total)),
+                    (Some(Blink(past + dt, dur, reached, total)),
                      Running, 0.0)
                 }
             },
@@ -210,9 +518,39 @@ impl AnimationState {
                 let factor = (t + dt) / d;
                 update_opacity(sprite, factor, t + dt, b, c, d)
             },
+            Tint(t, b, c, d) => {
+                let factor = (t + dt) / d;
+                update_tint(sprite, factor, t + dt, b, c, d)
+            },
+            Hsv(t, b, c, d) => {
+                let factor = (t + dt) / d;
+                update_hsv(sprite, factor, t + dt, b, c, d)
+            },
+            Reveal(t, n, d) => {
+                let factor = (t + dt) / d;
+                update_reveal(sprite, factor, t + dt, n, d)
+            },
+            ClipRect(t, b, c, d) => {
+                let factor = (t + dt) / d;
+                update_clip(sprite, factor, t + dt, b, c, d)
+            },
+            FollowPath(t, _, _, d) | Spline(t, _, _, d) |
+            Anchor(t, _, _, d) | SrcRect(t, _, _, d) => {
+                self.update_tween(sprite, t + dt, d)
+            },
+            Reverse(_) | Yoyo(..) => {
+                // `Scene::run` only accepts tweens in them
+                let (t, d) = self.tween_time().expect("only tweens can be played backward");
+                self.update_tween(sprite, t + dt, d)
+            },
             Ease(f, ref state) => {
                 let mut support_ease = true;
                 let (state, status, remain) = match **state {
+                    FollowPath(..) | Spline(..) | Anchor(..) | SrcRect(..) |
+                    Reverse(_) | Yoyo(..) if self.tween_time().is_some() => {
+                        let (t, d) = self.tween_time().unwrap();
+                        return self.update_tween(sprite, t + dt, d);
+                    },
                     Move(t, bx, by, cx, cy, d) => {
                         let factor = ::interpolation::Ease::calc((t + dt) / d, f);
                         update_position(sprite, factor, t + dt,
@@ -230,6 +568,22 @@ impl AnimationState {
                         let factor = ::interpolation::Ease::calc((t + dt) / d, f);
                         update_opacity(sprite, factor, t + dt, b, c, d)
                     },
+                    Tint(t, b, c, d) => {
+                        let factor = ::interpolation::Ease::calc((t + dt) / d, f);
+                        update_tint(sprite, factor, t + dt, b, c, d)
+                    },
+                    Hsv(t, b, c, d) => {
+                        let factor = ::interpolation::Ease::calc((t + dt) / d, f);
+                        update_hsv(sprite, factor, t + dt, b, c, d)
+                    },
+                    Reveal(t, n, d) => {
+                        let factor = ::interpolation::Ease::calc((t + dt) / d, f);
+                        update_reveal(sprite, factor, t + dt, n, d)
+                    },
+                    ClipRect(t, b, c, d) => {
+                        let factor = ::interpolation::Ease::calc((t + dt) / d, f);
+                        update_clip(sprite, factor, t + dt, b, c, d)
+                    },
                     _ => {
                         support_ease = false;
                         state.update(sprite, dt)
@@ -249,8 +603,168 @@ impl AnimationState {
             },
         }
     }
+    // The time and duration of the tween under the ease, reverse
+    // and yoyo wrappers
+    fn tween_time(&self) -> Option<(f64, f64)> {
+        use AnimationState::*;
+
+        match *self {
+            Move(t, _, _, _, _, d) |
+            Rotate(t, _, _, d) |
+            Scale(t, _, _, _, _, d) |
+            Fade(t, _, _, d) |
+            Tint(t, _, _, d) |
+            Hsv(t, _, _, d) |
+            Reveal(t, _, d) |
+            ClipRect(t, _, _, d) |
+            FollowPath(t, _, _, d) |
+            Spline(t, _, _, d) |
+            Anchor(t, _, _, d) |
+            SrcRect(t, _, _, d) => Some((t, d)),
+            Ease(_, ref state) | Reverse(ref state) | Yoyo(ref state, ..) => state.tween_time(),
+            _ => None,
+        }
+    }
+
+    fn set_tween_time(&mut self, time: f64) {
+        use AnimationState::*;
+
+        match *self {
+            Move(ref mut t, ..) |
+            Rotate(ref mut t, ..) |
+            Scale(ref mut t, ..) |
+            Fade(ref mut t, ..) |
+            Tint(ref mut t, ..) |
+            Hsv(ref mut t, ..) |
+            Reveal(ref mut t, ..) |
+            ClipRect(ref mut t, ..) |
+            FollowPath(ref mut t, ..) |
+            Spline(ref mut t, ..) |
+            Anchor(ref mut t, ..) |
+            SrcRect(ref mut t, ..) => *t = time,
+            Ease(_, ref mut state) |
+            Reverse(ref mut state) |
+            Yoyo(ref mut state, ..) => state.set_tween_time(time),
+            _ => {},
+        }
+    }
+
+    // Whether or not the first yoyo is played no times
+    fn no_legs(&self) -> bool {
+        use AnimationState::*;
+
+        match *self {
+            Yoyo(_, _, legs) => legs == Some(0),
+            Ease(_, ref state) | Reverse(ref state) => state.no_legs(),
+            _ => false,
+        }
+    }
+
+    // Move the first yoyo to its next leg,
+    // returns false if there is none or it played all its legs
+    fn next_leg(&mut self) -> bool {
+        use AnimationState::*;
+
+        match *self {
+            Yoyo(_, ref mut leg, legs) => {
+                if legs.is_some_and(|legs| *leg + 1 >= legs) {
+                    return false;
+                }
+                *leg += 1;
+                true
+            },
+            Ease(_, ref mut state) | Reverse(ref mut state) => state.next_leg(),
+            _ => false,
+        }
+    }
+
+    // Set the sprite's properties `u` of the way through the tween,
+    // after the wrappers changed it
+    fn sample<I: ImageSize>(&self, sprite: &mut Sprite<I>, u: f64) {
+        use AnimationState::*;
+
+        match *self {
+            Move(_, bx, by, cx, cy, _) => sprite.set_position(bx + cx * u, by + cy * u),
+            Rotate(_, b, c, _) => sprite.set_rotation(b + c * u),
+            Scale(_, bx, by, cx, cy, _) => sprite.set_scale(bx + cx * u, by + cy * u),
+            Fade(_, b, c, _) => sprite.set_opacity((b + c * u) as f32),
+            Tint(_, b, c, _) => set_rgb(sprite, [b[0] + c[0] * u, b[1] + c[1] * u, b[2] + c[2] * u]),
+            Hsv(_, b, c, _) => {
+                set_rgb(sprite, hsv_to_rgb([b[0] + c[0] * u, b[1] + c[1] * u, b[2] + c[2] * u]));
+            },
+            Reveal(_, n, _) => {
+                if let Content::BitmapText(ref mut text) = *sprite.get_content_mut() {
+                    text.set_visible_chars(if u >= 1.0 { None } else { Some((n * u).max(0.0) as usize) });
+                }
+            },
+            ClipRect(_, b, c, _) => {
+                sprite.set_clip(Some(Clip::Rect([
+                    b[0] + c[0] * u,
+                    b[1] + c[1] * u,
+                    b[2] + c[2] * u,
+                    b[3] + c[3] * u,
+                ])));
+            },
+            FollowPath(_, begin, ref path, _) => {
+                let (p, direction) = path.sample(u);
+                sprite.set_position(begin[0] + p[0], begin[1] + p[1]);
+                if let (true, Some(direction)) = (path.get_auto_rotate(), direction) {
+                    sprite.set_rotation(direction[1].atan2(direction[0]).to_degrees());
+                }
+            },
+            Spline(_, ref path, timing, _) => {
+                let (p, _) = match timing {
+                    SplineTiming::PerSegment => path.sample_segments(u),
+                    SplineTiming::ConstantSpeed => path.sample(u),
+                };
+                sprite.set_position(p[0], p[1]);
+            },
+            Anchor(_, b, c, _) => sprite.set_anchor(b[0] + c[0] * u, b[1] + c[1] * u),
+            SrcRect(_, b, c, _) => {
+                sprite.set_src_rect([
+                    b[0] + c[0] * u,
+                    b[1] + c[1] * u,
+                    b[2] + c[2] * u,
+                    b[3] + c[3] * u,
+                ]);
+            },
+            Ease(f, ref state) => state.sample(sprite, ::interpolation::Ease::calc(u, f)),
+            Reverse(ref state) => state.sample(sprite, 1.0 - u),
+            Yoyo(ref state, leg, _) => state.sample(sprite, if leg % 2 == 1 { 1.0 - u } else { u }),
+            _ => {},
+        }
+    }
+
+    // Update a tween under reverse or yoyo wrappers, `t` is the new time
+    // of the tween and `d` its duration
+    fn update_tween<I: ImageSize>(
+        &self,
+        sprite: &mut Sprite<I>,
+        mut t: f64,
+        d: f64
+    ) -> (Option<AnimationState>, Status, f64) {
+        if self.no_legs() {
+            return (None, Success, t);
+        }
+        let mut state = self.clone();
+        if d <= 0.0 {
+            state.sample(sprite, 1.0);
+            return (None, Success, t);
+        }
+        while t >= d {
+            if !state.next_leg() {
+                state.sample(sprite, 1.0);
+                return (None, Success, t - d);
+            }
+            t -= d;
+        }
+        state.set_tween_time(t);
+        state.sample(sprite, t / d);
+        (Some(state), Running, 0.0)
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_position<I: ImageSize>(
     sprite: &mut Sprite<I>,
     factor: f64,
@@ -289,6 +803,7 @@ fn update_rotation<I: ImageSize>(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_scale<I: ImageSize>(
     sprite: &mut Sprite<I>,
     factor: f64,
@@ -326,3 +841,131 @@ fn update_opacity<I: ImageSize>(
          Running, 0.0)
     }
 }
+
+fn update_tint<I: ImageSize>(
+    sprite: &mut Sprite<I>,
+    factor: f64,
+    t: f64,
+    b: [f64; 3],
+    c: [f64; 3],
+    d: f64
+) -> (Option<AnimationState>, Status, f64) {
+    if t >= d {
+        set_rgb(sprite, [b[0] + c[0], b[1] + c[1], b[2] + c[2]]);
+        (None, Success, t - d)
+    } else {
+        set_rgb(sprite, [b[0] + c[0] * factor, b[1] + c[1] * factor, b[2] + c[2] * factor]);
+        (Some(AnimationState::Tint(t, b, c, d)),
+         Running, 0.0)
+    }
+}
+
+fn update_hsv<I: ImageSize>(
+    sprite: &mut Sprite<I>,
+    factor: f64,
+    t: f64,
+    b: [f64; 3],
+    c: [f64; 3],
+    d: f64
+) -> (Option<AnimationState>, Status, f64) {
+    if t >= d {
+        set_rgb(sprite, hsv_to_rgb([b[0] + c[0], b[1] + c[1], b[2] + c[2]]));
+        (None, Success, t - d)
+    } else {
+        set_rgb(sprite, hsv_to_rgb([b[0] + c[0] * factor, b[1] + c[1] * factor, b[2] + c[2] * factor]));
+        (Some(AnimationState::Hsv(t, b, c, d)),
+         Running, 0.0)
+    }
+}
+
+fn update_reveal<I: ImageSize>(
+    sprite: &mut Sprite<I>,
+    factor: f64,
+    t: f64,
+    n: f64,
+    d: f64
+) -> (Option<AnimationState>, Status, f64) {
+    let text = match *sprite.get_content_mut() {
+        Content::BitmapText(ref mut text) => text,
+        _ => return (None, Success, t.max(d) - d),
+    };
+    if t >= d {
+        text.set_visible_chars(None);
+        (None, Success, t - d)
+    } else {
+        text.set_visible_chars(Some((n * factor).max(0.0) as usize));
+        (Some(AnimationState::Reveal(t, n, d)),
+         Running, 0.0)
+    }
+}
+
+fn update_clip<I: ImageSize>(
+    sprite: &mut Sprite<I>,
+    factor: f64,
+    t: f64,
+    b: [Scalar; 4],
+    c: [Scalar; 4],
+    d: f64
+) -> (Option<AnimationState>, Status, f64) {
+    let factor = if t >= d { 1.0 } else { factor };
+    sprite.set_clip(Some(Clip::Rect([
+        b[0] + c[0] * factor,
+        b[1] + c[1] * factor,
+        b[2] + c[2] * factor,
+        b[3] + c[3] * factor,
+    ])));
+    if t >= d {
+        (None, Success, t - d)
+    } else {
+        (Some(AnimationState::ClipRect(t, b, c, d)),
+         Running, 0.0)
+    }
+}
+
+fn get_rgb<I: ImageSize>(sprite: &Sprite<I>) -> [f64; 3] {
+    let (r, g, b) = sprite.get_color();
+    [r as f64, g as f64, b as f64]
+}
+
+fn set_rgb<I: ImageSize>(sprite: &mut Sprite<I>, rgb: [f64; 3]) {
+    sprite.set_color(rgb[0] as f32, rgb[1] as f32, rgb[2] as f32);
+}
+
+// [h, s, v] with hue in degree
+fn rgb_to_hsv(rgb: [f64; 3]) -> [f64; 3] {
+    let [r, g, b] = rgb;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let h = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let s = if max == 0.0 { 0.0 } else { delta / max };
+    [h, s, max]
+}
+
+fn hsv_to_rgb(hsv: [f64; 3]) -> [f64; 3] {
+    let h = hsv[0].rem_euclid(360.0) / 60.0;
+    let s = hsv[1].clamp(0.0, 1.0);
+    let v = hsv[2].clamp(0.0, 1.0);
+
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let m = v - c;
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    [r + m, g + m, b + m]
+}
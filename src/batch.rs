@@ -0,0 +1,168 @@
+use std::ptr;
+
+use graphics::{ DrawState, Graphics, ImageSize, BACK_END_MAX_VERTEX_COUNT };
+use graphics::math::Matrix2d;
+use graphics::triangulation::{ rect_tri_list_xy, rect_tri_list_uv };
+use graphics::types::{ Color, Rectangle, SourceRectangle };
+
+/// A textured quad waiting to be drawn by a `SpriteBatch`
+#[derive(Clone, Copy)]
+pub struct Quad<'a, I: 'a + ImageSize> {
+    /// The texture of the quad
+    pub texture: &'a I,
+    /// The world transform of the quad
+    pub transform: Matrix2d,
+    /// The rectangle the texture is drawn in, before `transform`
+    pub rect: Rectangle,
+    /// The source rectangle within the texture
+    pub src_rect: SourceRectangle,
+    /// The color the texture is multiplied with
+    pub color: Color,
+}
+
+// A quad turned into vertices, with its screen space bounds
+struct Vertices {
+    xy: [[f32; 2]; 6],
+    uv: [[f32; 2]; 6],
+    color: Color,
+    bounds: [f32; 4],
+}
+
+// Quads sharing a texture
+struct Batch<'a, I: 'a + ImageSize> {
+    texture: &'a I,
+    quads: Vec<Vertices>,
+    bounds: [f32; 4],
+}
+
+impl<'a, I: ImageSize> Batch<'a, I> {
+    fn overlaps(&self, bounds: &[f32; 4]) -> bool {
+        overlaps(&self.bounds, bounds)
+            && self.quads.iter().any(|q| overlaps(&q.bounds, bounds))
+    }
+}
+
+/// Collects quads and draws them grouped by texture
+///
+/// A quad is moved in front of quads with other textures only when it does
+/// not overlap any of them, so the result looks the same as drawing every
+/// quad in the order it was pushed, with fewer texture switches.
+pub struct SpriteBatch<'a, I: 'a + ImageSize> {
+    batches: Vec<Batch<'a, I>>,
+    len: usize,
+}
+
+impl<'a, I: ImageSize> Default for SpriteBatch<'a, I> {
+    fn default() -> SpriteBatch<'a, I> {
+        SpriteBatch::new()
+    }
+}
+
+impl<'a, I: ImageSize> SpriteBatch<'a, I> {
+    /// Create an empty batch
+    pub fn new() -> SpriteBatch<'a, I> {
+        SpriteBatch {
+            batches: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Add a quad, in draw order
+    pub fn push(&mut self, quad: Quad<'a, I>) {
+        let xy = rect_tri_list_xy(quad.transform, quad.rect);
+        let bounds = vertex_bounds(&xy);
+        let vertices = Vertices {
+            xy,
+            uv: rect_tri_list_uv(quad.texture, quad.src_rect),
+            color: quad.color,
+            bounds,
+        };
+        self.len += 1;
+
+        // look for the latest batch with the same texture
+        // that the quad can join without being drawn over something it covers
+        for batch in self.batches.iter_mut().rev() {
+            if ptr::eq(batch.texture, quad.texture) {
+                batch.quads.push(vertices);
+                batch.bounds = union(&batch.bounds, &bounds);
+                return;
+            }
+            if batch.overlaps(&bounds) {
+                break;
+            }
+        }
+
+        self.batches.push(Batch {
+            texture: quad.texture,
+            quads: vec![vertices],
+            bounds,
+        });
+    }
+
+    /// Get the number of quads in the batch
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether or not the batch has no quads
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the number of texture groups, which is the number of draw calls
+    /// `draw` will make
+    #[inline(always)]
+    pub fn groups(&self) -> usize {
+        self.batches.len()
+    }
+
+    /// Remove all the quads
+    pub fn clear(&mut self) {
+        self.batches.clear();
+        self.len = 0;
+    }
+
+    /// Draw all the quads, one call to the back-end per texture group
+    pub fn draw<B: Graphics<Texture = I>>(&self, b: &mut B) {
+        const QUADS_PER_CHUNK: usize = BACK_END_MAX_VERTEX_COUNT / 6;
+
+        let draw_state = &DrawState::default();
+        for batch in &self.batches {
+            b.tri_list_uv_c(draw_state, batch.texture, |f| {
+                for chunk in batch.quads.chunks(QUADS_PER_CHUNK) {
+                    let mut xy = Vec::with_capacity(chunk.len() * 6);
+                    let mut uv = Vec::with_capacity(chunk.len() * 6);
+                    let mut colors = Vec::with_capacity(chunk.len() * 6);
+                    for q in chunk {
+                        xy.extend_from_slice(&q.xy);
+                        uv.extend_from_slice(&q.uv);
+                        colors.extend_from_slice(&[q.color; 6]);
+                    }
+                    f(&xy, &uv, &colors);
+                }
+            });
+        }
+    }
+}
+
+fn vertex_bounds(vertices: &[[f32; 2]; 6]) -> [f32; 4] {
+    let mut bounds = [vertices[0][0], vertices[0][1], vertices[0][0], vertices[0][1]];
+    for v in vertices.iter() {
+        bounds[0] = bounds[0].min(v[0]);
+        bounds[1] = bounds[1].min(v[1]);
+        bounds[2] = bounds[2].max(v[0]);
+        bounds[3] = bounds[3].max(v[1]);
+    }
+    bounds
+}
+
+fn union(a: &[f32; 4], b: &[f32; 4]) -> [f32; 4] {
+    [a[0].min(b[0]), a[1].min(b[1]), a[2].max(b[2]), a[3].max(b[3])]
+}
+
+fn overlaps(a: &[f32; 4], b: &[f32; 4]) -> bool {
+    a[0] < b[2] && b[0] < a[2] && a[1] < b[3] && b[1] < a[3]
+}
+
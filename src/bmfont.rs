@@ -0,0 +1,438 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::f64::consts::PI;
+use std::fmt;
+use std::rc::Rc;
+
+use graphics::ImageSize;
+use graphics::math::{ Scalar, Vec2d };
+use graphics::types::{ Color, Rectangle, SourceRectangle };
+
+use text::{ align_offset, layout_lines, Layout, TextAlign };
+
+// The most page textures a font can have
+const MAX_PAGES: usize = 256;
+
+/// A character of a bitmap font
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BitmapGlyph {
+    /// The rectangle of the character in its page texture
+    pub src_rect: SourceRectangle,
+    /// The offset from the pen position to draw the character at,
+    /// relative to the top of the line
+    pub offset: Vec2d,
+    /// How far the pen moves after the character
+    pub advance: Scalar,
+    /// The index of the page texture
+    pub page: usize,
+}
+
+/// An error while parsing a bitmap font
+#[derive(Clone, PartialEq, Debug)]
+pub struct BitmapFontError {
+    /// The line the error was found on, starting at 1
+    pub line: usize,
+    /// What is wrong
+    pub message: String,
+}
+
+impl fmt::Display for BitmapFontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for BitmapFontError {}
+
+/// A bitmap font described by a BMFont (AngelCode) `.fnt` file in text format
+#[derive(Clone, Debug)]
+pub struct BitmapFont {
+    line_height: Scalar,
+    base: Scalar,
+    pages: Vec<String>,
+    glyphs: HashMap<char, BitmapGlyph>,
+    kernings: HashMap<(char, char), Scalar>,
+}
+
+impl BitmapFont {
+    /// Parse the content of a `.fnt` file in text format
+    pub fn parse(source: &str) -> Result<BitmapFont, BitmapFontError> {
+        let mut font = BitmapFont {
+            line_height: 0.0,
+            base: 0.0,
+            pages: Vec::new(),
+            glyphs: HashMap::new(),
+            kernings: HashMap::new(),
+        };
+
+        for (i, line) in source.lines().enumerate() {
+            let (tag, attributes) = match parse_line(line) {
+                Some(parsed) => parsed,
+                None => continue,
+            };
+            let error = |message: String| BitmapFontError { line: i + 1, message };
+            let number = |key: &str| -> Result<Scalar, BitmapFontError> {
+                let value = attributes.get(key)
+                    .ok_or_else(|| error(format!("missing `{}` in `{}`", key, tag)))?;
+                value.parse()
+                    .map_err(|_| error(format!("`{}` is not a number: {}", key, value)))
+            };
+            let index = |key: &str| -> Result<u32, BitmapFontError> {
+                let value = attributes.get(key)
+                    .ok_or_else(|| error(format!("missing `{}` in `{}`", key, tag)))?;
+                value.parse()
+                    .map_err(|_| error(format!("`{}` is not an index: {}", key, value)))
+            };
+            let page = |key: &str| -> Result<usize, BitmapFontError> {
+                let id = index(key)? as usize;
+                if id < MAX_PAGES {
+                    Ok(id)
+                } else {
+                    Err(error(format!("`{}` is more than {} pages: {}", key, MAX_PAGES, id)))
+                }
+            };
+            let character = |key: &str| -> Result<char, BitmapFontError> {
+                let id = index(key)?;
+                ::std::char::from_u32(id)
+                    .ok_or_else(|| error(format!("`{}` is not a character: {}", key, id)))
+            };
+
+            match tag {
+                "common" => {
+                    font.line_height = number("lineHeight")?;
+                    font.base = number("base")?;
+                },
+                "page" => {
+                    let id = page("id")?;
+                    let file = attributes.get("file")
+                        .ok_or_else(|| error("missing `file` in `page`".to_owned()))?;
+                    if font.pages.len() <= id {
+                        font.pages.resize(id + 1, String::new());
+                    }
+                    font.pages[id] = file.clone();
+                },
+                "char" => {
+                    let glyph = BitmapGlyph {
+                        src_rect: [number("x")?, number("y")?, number("width")?, number("height")?],
+                        offset: [number("xoffset")?, number("yoffset")?],
+                        advance: number("xadvance")?,
+                        page: attributes.get("page").map_or(Ok(0), |_| page("page"))?,
+                    };
+                    font.glyphs.insert(character("id")?, glyph);
+                },
+                "kerning" => {
+                    font.kernings.insert((character("first")?, character("second")?), number("amount")?);
+                },
+                _ => {},
+            }
+        }
+
+        if font.line_height <= 0.0 {
+            return Err(BitmapFontError { line: 0, message: "missing `common` line".to_owned() });
+        }
+        Ok(font)
+    }
+
+    /// Get the distance between lines
+    #[inline(always)]
+    pub fn get_line_height(&self) -> Scalar {
+        self.line_height
+    }
+
+    /// Get the distance from the top of a line to the baseline
+    #[inline(always)]
+    pub fn get_base(&self) -> Scalar {
+        self.base
+    }
+
+    /// Get the file names of the page textures, by page index
+    #[inline(always)]
+    pub fn pages(&self) -> &[String] {
+        &self.pages
+    }
+
+    /// Find a character of the font
+    #[inline(always)]
+    pub fn glyph(&self, ch: char) -> Option<&BitmapGlyph> {
+        self.glyphs.get(&ch)
+    }
+
+    /// Get the extra advance between two characters
+    #[inline(always)]
+    pub fn kerning(&self, first: char, second: char) -> Scalar {
+        self.kernings.get(&(first, second)).cloned().unwrap_or(0.0)
+    }
+
+    /// Get the width of a line of text
+    pub fn width(&self, text: &str) -> Scalar {
+        self.width_after(None, text)
+    }
+
+    // The width of `text` following `prev`, with the kerning between them
+    pub(crate) fn width_after(&self, mut prev: Option<char>, text: &str) -> Scalar {
+        let mut width = 0.0;
+        for ch in text.chars() {
+            if let Some(glyph) = self.glyph(ch) {
+                if let Some(prev) = prev {
+                    width += self.kerning(prev, ch);
+                }
+                width += glyph.advance;
+                prev = Some(ch);
+            }
+        }
+        width
+    }
+}
+
+// Split `tag key=value key="quoted value"`
+fn parse_line(line: &str) -> Option<(&str, HashMap<&str, String>)> {
+    let line = line.trim();
+    let (tag, mut rest) = match line.find(char::is_whitespace) {
+        Some(i) => (&line[..i], line[i..].trim_start()),
+        None if !line.is_empty() => (line, ""),
+        None => return None,
+    };
+
+    let mut attributes = HashMap::new();
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim();
+        rest = &rest[eq + 1..];
+        let value = if rest.starts_with('"') {
+            let end = rest[1..].find('"').map_or(rest.len(), |i| i + 1);
+            let value = &rest[1..end];
+            rest = &rest[(end + 1).min(rest.len())..];
+            value
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let value = &rest[..end];
+            rest = &rest[end..];
+            value
+        };
+        attributes.insert(key, value.to_owned());
+        rest = rest.trim_start();
+    }
+    Some((tag, attributes))
+}
+
+/// How a single character of a bitmap text is drawn
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GlyphStyle {
+    /// Offset from the laid out position of the character
+    pub offset: Vec2d,
+    /// Multiplied with the color of the sprite
+    pub color: Color,
+}
+
+impl Default for GlyphStyle {
+    fn default() -> GlyphStyle {
+        GlyphStyle {
+            offset: [0.0, 0.0],
+            color: [1.0; 4],
+        }
+    }
+}
+
+/// A per-character hook, called with the index of the character,
+/// the character and the time of the text in seconds
+pub type GlyphEffect = Rc<dyn Fn(usize, char, f64) -> GlyphStyle>;
+
+/// Move the characters up and down along a sine wave
+///
+/// `wavelength` is in characters and `speed` in waves per second.
+pub fn wave(amplitude: Scalar, wavelength: f64, speed: f64) -> GlyphEffect {
+    Rc::new(move |index, _, time| {
+        let phase = 2.0 * PI * (time * speed - index as f64 / wavelength);
+        GlyphStyle {
+            offset: [0.0, amplitude * phase.sin()],
+            ..GlyphStyle::default()
+        }
+    })
+}
+
+/// A text drawn from a bitmap font, the content of a bitmap text sprite
+///
+/// The text is drawn with the sprite's color and opacity,
+/// and the sprite's anchor is relative to the size of the laid out text.
+pub struct BitmapText<I: ImageSize> {
+    font: Rc<BitmapFont>,
+    pages: Vec<Rc<I>>,
+    text: String,
+    align: TextAlign,
+    wrap_width: Option<Scalar>,
+    visible_chars: Option<usize>,
+    effect: Option<GlyphEffect>,
+    time: f64,
+    layout: RefCell<Option<Rc<Layout>>>,
+}
+
+impl<I: ImageSize> BitmapText<I> {
+    /// Create a text drawn with `font`, `pages` are the textures of the font's pages
+    pub fn new(font: Rc<BitmapFont>, pages: Vec<Rc<I>>, text: &str) -> BitmapText<I> {
+        BitmapText {
+            font,
+            pages,
+            text: text.to_owned(),
+            align: TextAlign::Left,
+            wrap_width: None,
+            visible_chars: None,
+            effect: None,
+            time: 0.0,
+            layout: RefCell::new(None),
+        }
+    }
+
+    /// Get the text's font
+    #[inline(always)]
+    pub fn get_font(&self) -> &Rc<BitmapFont> {
+        &self.font
+    }
+
+    /// Get the text
+    #[inline(always)]
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Set the text
+    #[inline(always)]
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_owned();
+        self.layout = RefCell::new(None);
+    }
+
+    /// Get the text's alignment
+    #[inline(always)]
+    pub fn get_align(&self) -> TextAlign {
+        self.align
+    }
+
+    /// Set the text's alignment
+    #[inline(always)]
+    pub fn set_align(&mut self, align: TextAlign) {
+        self.align = align;
+    }
+
+    /// Get the width lines are wrapped at
+    #[inline(always)]
+    pub fn get_wrap_width(&self) -> Option<Scalar> {
+        self.wrap_width
+    }
+
+    /// Set the width lines are wrapped at, `None` to only break lines at '\n'
+    #[inline(always)]
+    pub fn set_wrap_width(&mut self, wrap_width: Option<Scalar>) {
+        self.wrap_width = wrap_width;
+        self.layout = RefCell::new(None);
+    }
+
+    /// Get how many characters are drawn, `None` if all of them are
+    #[inline(always)]
+    pub fn get_visible_chars(&self) -> Option<usize> {
+        self.visible_chars
+    }
+
+    /// Only draw the first `n` characters, this is what the `RevealText`
+    /// animation changes for a typewriter effect
+    #[inline(always)]
+    pub fn set_visible_chars(&mut self, n: Option<usize>) {
+        self.visible_chars = n;
+    }
+
+    /// Set the per-character hook, see `wave`
+    #[inline(always)]
+    pub fn set_effect(&mut self, effect: Option<GlyphEffect>) {
+        self.effect = effect;
+    }
+
+    /// Get the time passed to the per-character hook
+    #[inline(always)]
+    pub fn get_time(&self) -> f64 {
+        self.time
+    }
+
+    /// Advance the time passed to the per-character hook,
+    /// the scene does this on every update
+    #[inline(always)]
+    pub fn update(&mut self, dt: f64) {
+        self.time += dt;
+    }
+
+    // The lines of text with their width, laid out again after a change
+    fn layout(&self) -> Rc<Layout> {
+        if let Some(ref layout) = *self.layout.borrow() {
+            return layout.clone();
+        }
+
+        let lines = layout_lines(&self.text, self.wrap_width, |prev, s| self.font.width_after(prev, s));
+        let layout = Rc::new(Layout::new(lines, self.font.line_height));
+        *self.layout.borrow_mut() = Some(layout.clone());
+        layout
+    }
+
+    /// Get the number of characters once laid out
+    pub fn char_count(&self) -> usize {
+        self.layout().lines.iter().map(|(line, _)| line.chars().count()).sum()
+    }
+
+    /// Get the size of the laid out text
+    pub fn size(&self) -> (Scalar, Scalar) {
+        self.layout().size
+    }
+
+    /// Call `f` with the texture, rectangle, source rectangle and color of
+    /// every visible character laid out in `rect`
+    pub fn for_each_glyph<'a, F>(&'a self, rect: Rectangle, color: Color, mut f: F)
+        where F: FnMut(&'a I, Rectangle, SourceRectangle, Color)
+    {
+        let mut index = 0;
+        let layout = self.layout();
+        for (i, &(ref line, line_width)) in layout.lines.iter().enumerate() {
+            let mut x = rect[0] + align_offset(self.align, rect[2], line_width);
+            let y = rect[1] + i as Scalar * self.font.line_height;
+            let mut prev = None;
+
+            for ch in line.chars() {
+                if self.visible_chars.is_some_and(|n| index >= n) {
+                    return;
+                }
+                let glyph = match self.font.glyph(ch) {
+                    Some(glyph) => glyph,
+                    None => {
+                        index += 1;
+                        continue;
+                    }
+                };
+                if let Some(prev) = prev {
+                    x += self.font.kerning(prev, ch);
+                }
+                prev = Some(ch);
+
+                let style = match self.effect {
+                    Some(ref effect) => effect(index, ch, self.time),
+                    None => GlyphStyle::default(),
+                };
+                if let Some(texture) = self.pages.get(glyph.page) {
+                    f(
+                        &**texture,
+                        [
+                            x + glyph.offset[0] + style.offset[0],
+                            y + glyph.offset[1] + style.offset[1],
+                            glyph.src_rect[2],
+                            glyph.src_rect[3],
+                        ],
+                        glyph.src_rect,
+                        [
+                            color[0] * style.color[0],
+                            color[1] * style.color[1],
+                            color[2] * style.color[2],
+                            color[3] * style.color[3],
+                        ],
+                    );
+                }
+                x += glyph.advance;
+                index += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use graphics::Transformed;
+use graphics::math::{ Scalar, Matrix2d, Vec2d };
+
+use interpolation::{ Ease, EaseFunction };
+
+/// A camera scrolls the world layers of a scene and applies screen effects
+/// to their view transform
+///
+/// Effects only alter the transform used for drawing,
+/// the sprites themselves are never moved.
+#[derive(Clone, Copy)]
+pub struct Camera {
+    position: Vec2d,
+    viewport_size: Vec2d,
+
+    trauma: f64,
+    trauma_decay: f64,
+    max_offset: Vec2d,
+    max_angle: Scalar,
+    frequency: f64,
+    time: f64,
+
+    // elapsed, duration, amount
+    zoom_punch: Option<(f64, f64, Scalar)>,
+
+    pivot: Vec2d,
+}
+
+impl Default for Camera {
+    fn default() -> Camera {
+        Camera::new()
+    }
+}
+
+impl Camera {
+    /// Create a camera without any active effect
+    pub fn new() -> Camera {
+        Camera {
+            position: [0.0, 0.0],
+            viewport_size: [0.0, 0.0],
+
+            trauma: 0.0,
+            trauma_decay: 1.0,
+            max_offset: [10.0, 10.0],
+            max_angle: 5.0,
+            frequency: 15.0,
+            time: 0.0,
+
+            zoom_punch: None,
+
+            pivot: [0.0, 0.0],
+        }
+    }
+
+    /// Get the camera's position, the world point drawn at the view's origin
+    #[inline(always)]
+    pub fn get_position(&self) -> (Scalar, Scalar) {
+        (self.position[0], self.position[1])
+    }
+
+    /// Set the camera's position
+    #[inline(always)]
+    pub fn set_position(&mut self, x: Scalar, y: Scalar) {
+        self.position = [x, y];
+    }
+
+    /// Get the size of the area the camera looks at
+    #[inline(always)]
+    pub fn get_viewport_size(&self) -> (Scalar, Scalar) {
+        (self.viewport_size[0], self.viewport_size[1])
+    }
+
+    /// Set the size of the area the camera looks at, usually the window size.
+    ///
+    /// It is needed to know how many times a wrapping layer must be repeated,
+    /// and where touches are in the window.
+    #[inline(always)]
+    pub fn set_viewport_size(&mut self, w: Scalar, h: Scalar) {
+        self.viewport_size = [w, h];
+    }
+
+    /// Get the camera's trauma, from 0 to 1
+    #[inline(always)]
+    pub fn get_trauma(&self) -> f64 {
+        self.trauma
+    }
+
+    /// Set the camera's trauma, clamped to [0, 1]
+    #[inline(always)]
+    pub fn set_trauma(&mut self, trauma: f64) {
+        self.trauma = trauma.clamp(0.0, 1.0);
+    }
+
+    /// Add trauma to the camera, this is how a screen shake is triggered
+    ///
+    /// The shake intensity is the square of the trauma,
+    /// so small hits stay subtle while big ones add up quickly.
+    #[inline(always)]
+    pub fn add_trauma(&mut self, trauma: f64) {
+        let total = self.trauma + trauma;
+        self.set_trauma(total);
+    }
+
+    /// Get how much trauma is removed per second
+    #[inline(always)]
+    pub fn get_trauma_decay(&self) -> f64 {
+        self.trauma_decay
+    }
+
+    /// Set how much trauma is removed per second
+    #[inline(always)]
+    pub fn set_trauma_decay(&mut self, decay: f64) {
+        self.trauma_decay = decay;
+    }
+
+    /// Get the maximum translation of a shake
+    #[inline(always)]
+    pub fn get_max_offset(&self) -> (Scalar, Scalar) {
+        (self.max_offset[0], self.max_offset[1])
+    }
+
+    /// Set the maximum translation of a shake
+    #[inline(always)]
+    pub fn set_max_offset(&mut self, x: Scalar, y: Scalar) {
+        self.max_offset = [x, y];
+    }
+
+    /// Get the maximum rotation of a shake (in degree)
+    #[inline(always)]
+    pub fn get_max_angle(&self) -> Scalar {
+        self.max_angle
+    }
+
+    /// Set the maximum rotation of a shake (in degree)
+    #[inline(always)]
+    pub fn set_max_angle(&mut self, deg: Scalar) {
+        self.max_angle = deg;
+    }
+
+    /// Get the shake noise frequency, in samples per second
+    #[inline(always)]
+    pub fn get_frequency(&self) -> f64 {
+        self.frequency
+    }
+
+    /// Set the shake noise frequency, in samples per second
+    #[inline(always)]
+    pub fn set_frequency(&mut self, frequency: f64) {
+        self.frequency = frequency;
+    }
+
+    /// Get the point that shake rotation and zoom are applied around
+    #[inline(always)]
+    pub fn get_pivot(&self) -> (Scalar, Scalar) {
+        (self.pivot[0], self.pivot[1])
+    }
+
+    /// Set the point that shake rotation and zoom are applied around,
+    /// usually the center of the screen
+    #[inline(always)]
+    pub fn set_pivot(&mut self, x: Scalar, y: Scalar) {
+        self.pivot = [x, y];
+    }
+
+    /// Zoom in by `amount` at once, then settle back to no zoom in `duration` seconds
+    pub fn zoom_punch(&mut self, duration: f64, amount: Scalar) {
+        self.zoom_punch = Some((0.0, duration, amount));
+    }
+
+    /// Get the current zoom factor, 1 when no zoom punch is active
+    pub fn get_zoom(&self) -> Scalar {
+        match self.zoom_punch {
+            Some((elapsed, duration, amount)) if duration > 0.0 => {
+                let factor = (elapsed / duration).min(1.0).calc(EaseFunction::QuadraticOut);
+                1.0 + amount * (1.0 - factor)
+            },
+            _ => 1.0,
+        }
+    }
+
+    /// Get the current shake as (offset_x, offset_y, deg)
+    pub fn get_shake(&self) -> (Scalar, Scalar, Scalar) {
+        let shake = self.trauma * self.trauma;
+        if shake == 0.0 {
+            return (0.0, 0.0, 0.0);
+        }
+
+        let t = self.time * self.frequency;
+        (
+            self.max_offset[0] * shake * noise(0, t),
+            self.max_offset[1] * shake * noise(1, t),
+            self.max_angle * shake * noise(2, t),
+        )
+    }
+
+    /// Whether or not any effect is active
+    pub fn is_active(&self) -> bool {
+        self.trauma > 0.0 || self.zoom_punch.is_some()
+    }
+
+    /// Advance the effects by `dt` seconds
+    pub fn update(&mut self, dt: f64) {
+        self.time += dt;
+
+        let trauma = self.trauma - self.trauma_decay * dt;
+        self.set_trauma(trauma);
+
+        if let Some((elapsed, duration, amount)) = self.zoom_punch {
+            self.zoom_punch = if elapsed + dt >= duration {
+                None
+            } else {
+                Some((elapsed + dt, duration, amount))
+            };
+        }
+    }
+
+    /// Apply the camera's position and effects to a view transform
+    pub fn transform(&self, t: Matrix2d) -> Matrix2d {
+        self.effects_transform(t).trans(-self.position[0], -self.position[1])
+    }
+
+    /// Apply only the camera's effects to a view transform
+    pub fn effects_transform(&self, t: Matrix2d) -> Matrix2d {
+        if !self.is_active() {
+            return t;
+        }
+
+        let (ox, oy, deg) = self.get_shake();
+        t.trans(self.pivot[0] + ox, self.pivot[1] + oy)
+         .rot_deg(deg)
+         .zoom(self.get_zoom())
+         .trans(-self.pivot[0], -self.pivot[1])
+    }
+}
+
+// Smooth value noise in [-1, 1], one independent channel per `seed`
+fn noise(seed: u32, t: f64) -> f64 {
+    let i = t.floor();
+    let f = t - i;
+    let a = lattice(seed, i as i64);
+    let b = lattice(seed, i as i64 + 1);
+    let s = f * f * (3.0 - 2.0 * f);
+    a + (b - a) * s
+}
+
+fn lattice(seed: u32, i: i64) -> f64 {
+    let mut h = (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ u64::from(seed).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    h ^= h >> 33;
+    (h >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+}
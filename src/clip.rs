@@ -0,0 +1,143 @@
+use graphics::{ self, DrawState, Graphics, ImageSize };
+use graphics::draw_state::Stencil;
+use graphics::math::Matrix2d;
+use graphics::types::Rectangle;
+
+use sprite::Sprite;
+
+/// How a sprite clips its children
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Clip {
+    /// Clip to a rectangle relative to the top left corner of the sprite's
+    /// content. It follows the sprite's position, rotation and scale,
+    /// and uses the stencil buffer.
+    Rect(Rectangle),
+    /// Clip to the sprite's own content, which is only drawn to the stencil
+    /// buffer. Textures clip to their whole rectangle on most back-ends,
+    /// shapes clip to their outline.
+    Content,
+    /// Clip to a rectangle of the window in pixels, with the scissor test.
+    /// It ignores the sprite's transform but does not use the stencil buffer.
+    Scissor([u32; 4]),
+}
+
+// A mask drawn to the stencil buffer
+enum Mask<'a, I: 'a + ImageSize> {
+    Rect(Matrix2d, Rectangle),
+    Content(&'a Sprite<I>, Matrix2d, Rectangle),
+}
+
+// The masks of the clipping sprites above the sprite being drawn
+//
+// The stencil buffer counts how many masks cover a pixel, so only pixels
+// covered by every mask are drawn. There is no decrement, so leaving a
+// nested mask clears the stencil buffer and draws the remaining masks again.
+pub(crate) struct ClipStack<'a, I: 'a + ImageSize> {
+    masks: Vec<Mask<'a, I>>,
+    scissor: Option<[u32; 4]>,
+}
+
+impl<'a, I: ImageSize> ClipStack<'a, I> {
+    pub fn new() -> ClipStack<'a, I> {
+        ClipStack {
+            masks: Vec::new(),
+            scissor: None,
+        }
+    }
+
+    // The draw state of content inside all the masks
+    pub fn draw_state(&self) -> DrawState {
+        DrawState {
+            scissor: self.scissor,
+            stencil: if self.masks.is_empty() {
+                None
+            } else {
+                Some(Stencil::Inside(self.masks.len() as u8))
+            },
+            ..DrawState::default()
+        }
+    }
+
+    // Start clipping the children of `sprite`, returns what `pop` needs
+    pub fn push<B>(
+        &mut self,
+        sprite: &'a Sprite<I>,
+        clip: Clip,
+        transformed: Matrix2d,
+        model: Matrix2d,
+        rect: Rectangle,
+        b: &mut B
+    ) -> Option<[u32; 4]>
+        where B: Graphics<Texture = I>
+    {
+        let saved = self.scissor;
+        let mask = match clip {
+            Clip::Scissor(scissor) => {
+                self.scissor = Some(match self.scissor {
+                    Some(outer) => intersect(outer, scissor),
+                    None => scissor,
+                });
+                return saved;
+            },
+            Clip::Rect(clip_rect) => {
+                Mask::Rect(transformed, [
+                    rect[0] + clip_rect[0],
+                    rect[1] + clip_rect[1],
+                    clip_rect[2],
+                    clip_rect[3],
+                ])
+            },
+            Clip::Content => Mask::Content(sprite, model, rect),
+        };
+
+        if self.masks.is_empty() {
+            b.clear_stencil(0);
+        }
+        self.draw_mask(&mask, b);
+        self.masks.push(mask);
+        saved
+    }
+
+    // Stop clipping with the latest `clip`
+    pub fn pop<B>(&mut self, clip: Clip, saved: Option<[u32; 4]>, b: &mut B)
+        where B: Graphics<Texture = I>
+    {
+        if let Clip::Scissor(_) = clip {
+            self.scissor = saved;
+            return;
+        }
+
+        self.masks.pop();
+        if !self.masks.is_empty() {
+            b.clear_stencil(0);
+            for mask in &self.masks {
+                self.draw_mask(mask, b);
+            }
+        }
+    }
+
+    fn draw_mask<B>(&self, mask: &Mask<'a, I>, b: &mut B)
+        where B: Graphics<Texture = I>
+    {
+        let draw_state = &DrawState {
+            scissor: self.scissor,
+            ..DrawState::new_increment()
+        };
+        match *mask {
+            Mask::Rect(t, rect) => {
+                graphics::Rectangle::new([1.0; 4]).draw(rect, draw_state, t, b);
+            },
+            Mask::Content(sprite, model, rect) => {
+                sprite.draw_mask(model, rect, draw_state, b);
+            },
+        }
+    }
+}
+
+fn intersect(a: [u32; 4], b: [u32; 4]) -> [u32; 4] {
+    let x = a[0].max(b[0]);
+    let y = a[1].max(b[1]);
+    let right = (a[0] + a[2]).min(b[0] + b[2]);
+    let bottom = (a[1] + a[3]).min(b[1] + b[3]);
+    [x, y, right.saturating_sub(x), bottom.saturating_sub(y)]
+}
@@ -0,0 +1,249 @@
+use uuid::Uuid;
+
+use graphics::ImageSize;
+use graphics::math::{ Scalar, Matrix2d, Vec2d, transform_pos, transform_vec };
+use graphics::types::Rectangle;
+
+use sprite::Sprite;
+
+/// The geometry of a collider, relative to the top left corner of the
+/// sprite's content
+#[derive(Clone, PartialEq, Debug)]
+pub enum CollisionShape {
+    /// A rectangle which stays axis aligned in world space,
+    /// it grows to cover the rotated rectangle
+    Aabb(Rectangle),
+    /// A rectangle which rotates with the sprite
+    Obb(Rectangle),
+    /// center, radius
+    ///
+    /// A circle, the radius is scaled by the larger scale of the sprite
+    Circle(Vec2d, Scalar),
+    /// A convex polygon through the points
+    Polygon(Vec<Vec2d>),
+}
+
+/// A collision shape attached to a sprite
+///
+/// Two colliders are checked against each other only when each one is on
+/// a layer the other one's mask contains.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Collider {
+    shape: CollisionShape,
+    layers: u32,
+    mask: u32,
+}
+
+impl Collider {
+    /// Create a collider on every layer, colliding with every layer
+    pub fn new(shape: CollisionShape) -> Collider {
+        Collider {
+            shape,
+            layers: !0,
+            mask: !0,
+        }
+    }
+
+    /// Get the collider's shape
+    #[inline(always)]
+    pub fn get_shape(&self) -> &CollisionShape {
+        &self.shape
+    }
+
+    /// Set the collider's shape
+    #[inline(always)]
+    pub fn set_shape(&mut self, shape: CollisionShape) {
+        self.shape = shape;
+    }
+
+    /// Get the bits of the layers the collider is on
+    #[inline(always)]
+    pub fn get_layers(&self) -> u32 {
+        self.layers
+    }
+
+    /// Set the bits of the layers the collider is on
+    #[inline(always)]
+    pub fn set_layers(&mut self, layers: u32) {
+        self.layers = layers;
+    }
+
+    /// Get the bits of the layers the collider collides with
+    #[inline(always)]
+    pub fn get_mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Set the bits of the layers the collider collides with
+    #[inline(always)]
+    pub fn set_mask(&mut self, mask: u32) {
+        self.mask = mask;
+    }
+
+    /// Whether or not the layer masks let the two colliders collide
+    #[inline(always)]
+    pub fn can_collide(&self, other: &Collider) -> bool {
+        self.layers & other.mask != 0 && other.layers & self.mask != 0
+    }
+}
+
+// A collider in world space
+#[derive(Clone, Debug)]
+pub(crate) enum WorldShape {
+    Polygon(Vec<Vec2d>),
+    Circle(Vec2d, Scalar),
+}
+
+impl WorldShape {
+    // `t` is the transform of the sprite and `origin` the top left corner
+    // of its content
+    fn new(shape: &CollisionShape, t: Matrix2d, origin: Vec2d) -> WorldShape {
+        let pos = |p: Vec2d| transform_pos(t, [origin[0] + p[0], origin[1] + p[1]]);
+        let corners = |r: Rectangle| vec![
+            pos([r[0], r[1]]),
+            pos([r[0] + r[2], r[1]]),
+            pos([r[0] + r[2], r[1] + r[3]]),
+            pos([r[0], r[1] + r[3]]),
+        ];
+
+        match *shape {
+            CollisionShape::Aabb(rect) => {
+                let (min, max) = extent(&corners(rect));
+                WorldShape::Polygon(vec![
+                    min, [max[0], min[1]], max, [min[0], max[1]],
+                ])
+            },
+            CollisionShape::Obb(rect) => WorldShape::Polygon(corners(rect)),
+            CollisionShape::Circle(center, radius) => {
+                let x = transform_vec(t, [radius, 0.0]);
+                let y = transform_vec(t, [0.0, radius]);
+                WorldShape::Circle(pos(center), length(x).max(length(y)))
+            },
+            CollisionShape::Polygon(ref points) => {
+                WorldShape::Polygon(points.iter().map(|&p| pos(p)).collect())
+            },
+        }
+    }
+
+    pub fn overlaps(&self, other: &WorldShape) -> bool {
+        use self::WorldShape::*;
+
+        match (self, other) {
+            (Circle(a, ra), Circle(b, rb)) => {
+                let d = sub(*a, *b);
+                dot(d, d) <= (ra + rb) * (ra + rb)
+            },
+            (Polygon(points), Circle(center, radius)) |
+            (Circle(center, radius), Polygon(points)) => {
+                polygon_overlaps_circle(points, *center, *radius)
+            },
+            (Polygon(a), Polygon(b)) => {
+                !separated(a, b) && !separated(b, a)
+            },
+        }
+    }
+}
+
+// The world space shapes of the colliders of `sprites` and their children
+pub(crate) fn world_colliders<'a, I: ImageSize>(
+    sprites: &'a [Sprite<I>],
+    t: Matrix2d,
+    out: &mut Vec<(Uuid, &'a Collider, WorldShape)>
+) {
+    for sprite in sprites {
+        // only colliders need the size of the content
+        let transformed = match sprite.get_collider() {
+            Some(collider) => {
+                let (transformed, _, rect) = sprite.draw_params(t);
+                let shape = WorldShape::new(collider.get_shape(), transformed, [rect[0], rect[1]]);
+                out.push((sprite.id(), collider, shape));
+                transformed
+            },
+            None => sprite.transform(t),
+        };
+        world_colliders(sprite.children(), transformed, out);
+    }
+}
+
+// Whether or not any of `sprites` or their children has a collider
+pub(crate) fn has_colliders<I: ImageSize>(sprites: &[Sprite<I>]) -> bool {
+    sprites.iter().any(|sprite| sprite.get_collider().is_some() || has_colliders(sprite.children()))
+}
+
+fn sub(a: Vec2d, b: Vec2d) -> Vec2d {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn dot(a: Vec2d, b: Vec2d) -> Scalar {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+fn length(v: Vec2d) -> Scalar {
+    dot(v, v).sqrt()
+}
+
+fn extent(points: &[Vec2d]) -> (Vec2d, Vec2d) {
+    let mut min = points[0];
+    let mut max = points[0];
+    for p in points {
+        min = [min[0].min(p[0]), min[1].min(p[1])];
+        max = [max[0].max(p[0]), max[1].max(p[1])];
+    }
+    (min, max)
+}
+
+// Project the points on `axis`
+fn project(points: &[Vec2d], axis: Vec2d) -> (Scalar, Scalar) {
+    points.iter()
+        .map(|&p| dot(p, axis))
+        .fold((Scalar::MAX, Scalar::MIN), |(min, max), d| (min.min(d), max.max(d)))
+}
+
+// Whether or not an edge normal of `a` separates the polygons
+fn separated(a: &[Vec2d], b: &[Vec2d]) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return true;
+    }
+    (0..a.len()).any(|i| {
+        let edge = sub(a[(i + 1) % a.len()], a[i]);
+        let axis = [-edge[1], edge[0]];
+        let (min_a, max_a) = project(a, axis);
+        let (min_b, max_b) = project(b, axis);
+        max_a < min_b || max_b < min_a
+    })
+}
+
+fn polygon_overlaps_circle(points: &[Vec2d], center: Vec2d, radius: Scalar) -> bool {
+    if points.is_empty() {
+        return false;
+    }
+
+    // the center is inside when it is on the same side of every edge
+    let mut sign = 0.0;
+    let mut inside = true;
+    for i in 0..points.len() {
+        let (p, q) = (points[i], points[(i + 1) % points.len()]);
+        let edge = sub(q, p);
+        let cross = edge[0] * (center[1] - p[1]) - edge[1] * (center[0] - p[0]);
+        if cross != 0.0 {
+            if sign != 0.0 && cross.signum() != sign {
+                inside = false;
+            }
+            sign = cross.signum();
+        }
+    }
+    if inside {
+        return true;
+    }
+
+    // otherwise the closest point of an edge is within the radius
+    (0..points.len()).any(|i| {
+        let (p, q) = (points[i], points[(i + 1) % points.len()]);
+        let edge = sub(q, p);
+        let len2 = dot(edge, edge);
+        let t = if len2 == 0.0 { 0.0 } else { (dot(sub(center, p), edge) / len2).clamp(0.0, 1.0) };
+        let closest = [p[0] + edge[0] * t, p[1] + edge[1] * t];
+        let d = sub(center, closest);
+        dot(d, d) <= radius * radius
+    })
+}
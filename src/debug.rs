@@ -0,0 +1,55 @@
+use graphics::math::Scalar;
+use graphics::types::{ Color, FontSize };
+
+/// Options for the overlay drawn by `Scene::draw_debug`
+#[derive(Clone, Copy)]
+pub struct DebugDraw {
+    /// Draw the overlay for every sprite, not only for the sprites with
+    /// `Sprite::set_debug(true)`
+    pub all: bool,
+    /// Draw the oriented bounds of sprites
+    pub bounds: bool,
+    /// Draw the anchor points of sprites
+    pub anchors: bool,
+    /// Draw a line from every sprite to each of its children
+    pub links: bool,
+    /// Draw the names of sprites, only used by `Scene::draw_debug_names`
+    pub names: bool,
+
+    /// The color of the bounds
+    pub bounds_color: Color,
+    /// The color of the anchor points
+    pub anchor_color: Color,
+    /// The color of the links
+    pub link_color: Color,
+    /// The color of the names
+    pub name_color: Color,
+
+    /// Half the width of the lines
+    pub line_radius: Scalar,
+    /// Half the size of the anchor markers
+    pub anchor_radius: Scalar,
+    /// The font size of the names
+    pub font_size: FontSize,
+}
+
+impl Default for DebugDraw {
+    fn default() -> DebugDraw {
+        DebugDraw {
+            all: false,
+            bounds: true,
+            anchors: true,
+            links: true,
+            names: true,
+
+            bounds_color: [1.0, 0.0, 0.0, 1.0],
+            anchor_color: [0.0, 0.0, 1.0, 1.0],
+            link_color: [0.0, 1.0, 0.0, 1.0],
+            name_color: [1.0, 1.0, 1.0, 1.0],
+
+            line_radius: 0.5,
+            anchor_radius: 3.0,
+            font_size: 12,
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use uuid::Uuid;
+
+use graphics::math::{ self, Scalar, Matrix2d, Vec2d };
+
+/// The name of the layer sprites are added to by `Scene::add_child`
+pub const DEFAULT_LAYER: &str = "default";
+
+/// A layer is a named group of top-level sprites which are drawn together
+///
+/// Layers are drawn from the lowest order to the highest,
+/// sprites inside a layer are drawn in the order they were added.
+pub struct Layer {
+    name: String,
+    order: i32,
+
+    visible: bool,
+    screen_space: bool,
+    transform: Matrix2d,
+    parallax: Vec2d,
+    wrap: [Option<Scalar>; 2],
+
+    children: Vec<Uuid>,
+}
+
+impl Layer {
+    /// Create an empty layer
+    pub fn new(name: &str, order: i32) -> Layer {
+        Layer {
+            name: name.to_owned(),
+            order,
+
+            visible: true,
+            screen_space: false,
+            transform: math::identity(),
+            parallax: [1.0, 1.0],
+            wrap: [None, None],
+
+            children: Vec::new(),
+        }
+    }
+
+    /// Get the layer's name
+    #[inline(always)]
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the layer's draw order, use `Scene::set_layer_order` to change it
+    #[inline(always)]
+    pub fn get_order(&self) -> i32 {
+        self.order
+    }
+
+    /// Whether or not the layer is visible
+    #[inline(always)]
+    pub fn get_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Set the layer's visibility
+    #[inline(always)]
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Whether or not the layer ignores the scene's camera
+    #[inline(always)]
+    pub fn get_screen_space(&self) -> bool {
+        self.screen_space
+    }
+
+    /// Set whether or not the layer ignores the scene's camera,
+    /// which is what UI layers usually want
+    #[inline(always)]
+    pub fn set_screen_space(&mut self, screen_space: bool) {
+        self.screen_space = screen_space;
+    }
+
+    /// Get the layer's transform
+    #[inline(always)]
+    pub fn get_transform(&self) -> Matrix2d {
+        self.transform
+    }
+
+    /// Set the layer's transform, it is applied after the view transform
+    #[inline(always)]
+    pub fn set_transform(&mut self, transform: Matrix2d) {
+        self.transform = transform;
+    }
+
+    /// Get the layer's parallax factors
+    #[inline(always)]
+    pub fn get_parallax(&self) -> (Scalar, Scalar) {
+        (self.parallax[0], self.parallax[1])
+    }
+
+    /// Set the fraction of the camera's offset the layer moves by.
+    ///
+    /// Default value is [1.0, 1.0], background layers use smaller factors
+    /// so they scroll slower than the world. Ignored for screen space layers.
+    #[inline(always)]
+    pub fn set_parallax(&mut self, x: Scalar, y: Scalar) {
+        self.parallax = [x, y];
+    }
+
+    /// Get the layer's wrap-around size in x and y direction
+    #[inline(always)]
+    pub fn get_wrap(&self) -> (Option<Scalar>, Option<Scalar>) {
+        (self.wrap[0], self.wrap[1])
+    }
+
+    /// Repeat the layer every `w` horizontally and/or every `h` vertically,
+    /// so a tiled background of that size covers the whole viewport
+    /// wherever the camera is.
+    #[inline(always)]
+    pub fn set_wrap(&mut self, w: Option<Scalar>, h: Option<Scalar>) {
+        self.wrap = [w, h];
+    }
+
+    /// Get the ids of the layer's sprites, in draw order
+    #[inline(always)]
+    pub fn children(&self) -> &[Uuid] {
+        &self.children
+    }
+
+    pub(crate) fn set_order(&mut self, order: i32) {
+        self.order = order;
+    }
+
+    pub(crate) fn add_child(&mut self, id: Uuid) {
+        self.children.push(id);
+    }
+
+    pub(crate) fn remove_child(&mut self, id: Uuid) -> bool {
+        if let Some(index) = self.children.iter().position(|c| *c == id) {
+            self.children.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+}
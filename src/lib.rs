@@ -15,10 +15,37 @@ pub use animation::{
     AnimationState,
 };
 pub use animation::Animation::*;
-pub use scene::Scene;
-pub use sprite::Sprite;
+pub use batch::{ Quad, SpriteBatch };
+pub use bmfont::{ wave, BitmapFont, BitmapFontError, BitmapGlyph, BitmapText, GlyphEffect, GlyphStyle };
+pub use camera::Camera;
+pub use clip::Clip;
+pub use collision::{ Collider, CollisionShape };
+pub use debug::DebugDraw;
+pub use layer::{ Layer, DEFAULT_LAYER };
+pub use particles::{ Curve, Emitter, Particle, ParticleSettings };
+pub use path::{ Path, PathSegment, SplineTiming };
+pub use pointer::{ DragAxis, PointerEvent, PointerEventKind, PointerHandler };
+pub use scene::{ AnimationEvent, AnimationHandler, AnimationId, Scene };
+pub use shape::{ Geometry, Shape, ShapeStyle };
+pub use sprite::{ Content, Sprite };
+pub use text::{ Glyphs, Label, TextAlign };
 pub use interpolation::EaseFunction;
 
 mod animation;
+mod batch;
+mod bmfont;
+mod camera;
+mod clip;
+mod collision;
+mod debug;
+mod layer;
+mod particles;
+mod path;
+mod pointer;
 mod scene;
+mod shape;
 mod sprite;
+mod text;
+
+#[cfg(feature = "testing")]
+pub mod testing;
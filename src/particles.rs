@@ -0,0 +1,289 @@
+use std::rc::Rc;
+
+use uuid::Uuid;
+
+use graphics::ImageSize;
+use graphics::math::{ Scalar, Vec2d };
+use graphics::types::{ Color, Rectangle, SourceRectangle };
+use interpolation::Lerp;
+
+/// A value changing over the life of a particle
+///
+/// Keys are `(life, value)` pairs with `life` from 0 (spawned) to 1 (dead),
+/// values in between are interpolated linearly.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Curve<T> {
+    keys: Vec<(f64, T)>,
+}
+
+impl<T> Curve<T> where T: Lerp + Copy, T::Scalar: From<f32> {
+    /// Create a curve through the keys, they are sorted by life
+    ///
+    /// # Panics
+    /// If there are no keys.
+    pub fn new(mut keys: Vec<(f64, T)>) -> Curve<T> {
+        assert!(!keys.is_empty(), "a curve needs at least one key");
+        keys.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(::std::cmp::Ordering::Equal));
+        Curve { keys }
+    }
+
+    /// Create a curve which keeps the same value
+    pub fn constant(value: T) -> Curve<T> {
+        Curve::new(vec![(0.0, value)])
+    }
+
+    /// Create a curve from `from` at spawn to `to` at death
+    pub fn linear(from: T, to: T) -> Curve<T> {
+        Curve::new(vec![(0.0, from), (1.0, to)])
+    }
+
+    /// Get the keys of the curve
+    #[inline(always)]
+    pub fn keys(&self) -> &[(f64, T)] {
+        &self.keys
+    }
+
+    /// Get the value at `life`, from 0 to 1
+    pub fn sample(&self, life: f64) -> T {
+        let i = self.keys.iter().position(|&(l, _)| l > life).unwrap_or(self.keys.len());
+        if i == 0 {
+            self.keys[0].1
+        } else if i == self.keys.len() {
+            self.keys[i - 1].1
+        } else {
+            let (l0, v0) = self.keys[i - 1];
+            let (l1, v1) = self.keys[i];
+            let t = ((life - l0) / (l1 - l0)) as f32;
+            v0.lerp(&v1, &T::Scalar::from(t))
+        }
+    }
+}
+
+/// How an `Emitter` spawns and animates its particles
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParticleSettings {
+    /// Particles spawned per second while emitting
+    pub rate: f64,
+    /// The particles alive at once are capped to this number
+    pub max_particles: usize,
+    /// The lifetime of particles in seconds, picked between the two values
+    pub lifetime: [f64; 2],
+    /// The initial speed of particles, picked between the two values
+    pub speed: [Scalar; 2],
+    /// The direction particles are emitted in, in degree
+    pub angle: Scalar,
+    /// The directions of particles spread this many degree around `angle`
+    pub spread: Scalar,
+    /// Acceleration applied to every particle
+    pub gravity: Vec2d,
+    /// The size of particles, relative to the source rectangle
+    pub size: Curve<Scalar>,
+    /// The color of particles, multiplied with the sprite's color
+    pub color: Curve<[f32; 3]>,
+    /// The opacity of particles, multiplied with the sprite's opacity
+    pub opacity: Curve<f32>,
+}
+
+impl Default for ParticleSettings {
+    fn default() -> ParticleSettings {
+        ParticleSettings {
+            rate: 10.0,
+            max_particles: 500,
+            lifetime: [1.0, 1.0],
+            speed: [50.0, 50.0],
+            angle: -90.0,
+            spread: 0.0,
+            gravity: [0.0, 0.0],
+            size: Curve::constant(1.0),
+            color: Curve::constant([1.0; 3]),
+            opacity: Curve::linear(1.0, 0.0),
+        }
+    }
+}
+
+/// A live particle of an `Emitter`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Particle {
+    /// The position, relative to the emitter
+    pub position: Vec2d,
+    /// The velocity, in pixels per second
+    pub velocity: Vec2d,
+    /// The time since the particle was spawned
+    pub age: f64,
+    /// The time the particle lives for
+    pub lifetime: f64,
+}
+
+impl Particle {
+    /// Get how far the particle is in its life, from 0 to 1
+    #[inline(always)]
+    pub fn life(&self) -> f64 {
+        (self.age / self.lifetime).min(1.0)
+    }
+}
+
+/// A particle emitter, the content of an emitter sprite
+///
+/// Particles are plain values instead of sprites, and live in the space of
+/// the emitter sprite. They are drawn with the emitter's texture, or the
+/// sprite's source rectangle of it.
+pub struct Emitter<I: ImageSize> {
+    texture: Rc<I>,
+    settings: ParticleSettings,
+    particles: Vec<Particle>,
+    emitting: bool,
+    pending: f64,
+    seed: u64,
+}
+
+impl<I: ImageSize> Emitter<I> {
+    /// Create an emitter drawing particles with `texture`
+    pub fn new(texture: Rc<I>, settings: ParticleSettings) -> Emitter<I> {
+        let bytes = Uuid::new_v4();
+        let mut seed = 0;
+        for b in &bytes.as_bytes()[..8] {
+            seed = seed << 8 | *b as u64;
+        }
+        Emitter {
+            texture,
+            settings,
+            particles: Vec::new(),
+            emitting: true,
+            pending: 0.0,
+            seed: seed | 1,
+        }
+    }
+
+    /// Get the emitter's texture
+    #[inline(always)]
+    pub fn get_texture(&self) -> &Rc<I> {
+        &self.texture
+    }
+
+    /// Set the emitter's texture
+    #[inline(always)]
+    pub fn set_texture(&mut self, texture: Rc<I>) {
+        self.texture = texture;
+    }
+
+    /// Get the emitter's settings
+    #[inline(always)]
+    pub fn get_settings(&self) -> &ParticleSettings {
+        &self.settings
+    }
+
+    /// Get the emitter's settings, mutability
+    #[inline(always)]
+    pub fn get_settings_mut(&mut self) -> &mut ParticleSettings {
+        &mut self.settings
+    }
+
+    /// Whether or not particles are spawned at `rate`
+    #[inline(always)]
+    pub fn get_emitting(&self) -> bool {
+        self.emitting
+    }
+
+    /// Start or stop spawning particles at `rate`,
+    /// live particles keep going until they die
+    #[inline(always)]
+    pub fn set_emitting(&mut self, emitting: bool) {
+        self.emitting = emitting;
+    }
+
+    /// Set the seed of the random numbers, to get the same particles every time
+    #[inline(always)]
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed | 1;
+    }
+
+    /// Get the live particles
+    #[inline(always)]
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    /// Remove every live particle
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.pending = 0.0;
+    }
+
+    /// Spawn `count` particles at once
+    pub fn burst(&mut self, count: usize) {
+        for _ in 0..count {
+            self.spawn();
+        }
+    }
+
+    /// Move and age the particles and spawn new ones,
+    /// the scene does this on every update
+    pub fn update(&mut self, dt: f64) {
+        let gravity = self.settings.gravity;
+        self.particles.retain(|p| p.age + dt < p.lifetime);
+        for p in &mut self.particles {
+            p.age += dt;
+            p.velocity = [p.velocity[0] + gravity[0] * dt, p.velocity[1] + gravity[1] * dt];
+            p.position = [p.position[0] + p.velocity[0] * dt, p.position[1] + p.velocity[1] * dt];
+        }
+
+        if self.emitting {
+            self.pending += self.settings.rate * dt;
+            while self.pending >= 1.0 {
+                self.pending -= 1.0;
+                self.spawn();
+            }
+        }
+    }
+
+    fn spawn(&mut self) {
+        if self.particles.len() >= self.settings.max_particles {
+            return;
+        }
+        let lifetime = self.random_between(self.settings.lifetime);
+        let speed = self.random_between(self.settings.speed);
+        let spread = self.settings.spread / 2.0;
+        let angle = self.random_between([self.settings.angle - spread, self.settings.angle + spread])
+            .to_radians();
+        self.particles.push(Particle {
+            position: [0.0, 0.0],
+            velocity: [speed * angle.cos(), speed * angle.sin()],
+            age: 0.0,
+            lifetime,
+        });
+    }
+
+    // xorshift64*, good enough for particles
+    fn random_between(&mut self, range: [f64; 2]) -> f64 {
+        self.seed ^= self.seed >> 12;
+        self.seed ^= self.seed << 25;
+        self.seed ^= self.seed >> 27;
+        let r = self.seed.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11;
+        range[0] + (range[1] - range[0]) * (r as f64 / (1u64 << 53) as f64)
+    }
+
+    /// Call `f` with the rectangle and color of every live particle,
+    /// oldest first
+    pub fn for_each_particle<F>(&self, src_rect: Option<SourceRectangle>, color: Color, mut f: F)
+        where F: FnMut(Rectangle, Color)
+    {
+        let (w, h) = match src_rect {
+            Some(src_rect) => (src_rect[2], src_rect[3]),
+            None => {
+                let (w, h) = self.texture.get_size();
+                (w as Scalar, h as Scalar)
+            }
+        };
+        for p in &self.particles {
+            let life = p.life();
+            let size = self.settings.size.sample(life);
+            let rgb = self.settings.color.sample(life);
+            let opacity = self.settings.opacity.sample(life);
+            let (pw, ph) = (w * size, h * size);
+            f(
+                [p.position[0] - pw / 2.0, p.position[1] - ph / 2.0, pw, ph],
+                [color[0] * rgb[0], color[1] * rgb[1], color[2] * rgb[2], color[3] * opacity],
+            );
+        }
+    }
+}
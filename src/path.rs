@@ -0,0 +1,257 @@
+use graphics::math::{ Scalar, Vec2d };
+
+// The samples measured along every curve of a path
+const SAMPLES: usize = 32;
+
+/// A piece of a `Path`, starting where the previous one ends
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PathSegment {
+    /// end
+    ///
+    /// A straight line
+    Line(Vec2d),
+    /// control, end
+    ///
+    /// A quadratic Bezier curve
+    Quadratic(Vec2d, Vec2d),
+    /// control_1, control_2, end
+    ///
+    /// A cubic Bezier curve
+    Cubic(Vec2d, Vec2d, Vec2d),
+}
+
+/// How the time of a spline is spread along it
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SplineTiming {
+    /// Every segment between two waypoints takes the same time
+    PerSegment,
+    /// Move at the same speed along the whole spline
+    ConstantSpeed,
+}
+
+/// A path made of lines and Bezier curves, followed by `MoveAlongPath`
+///
+/// Points are relative to the sprite's position when the animation starts.
+/// The path is measured when segments are added, so sprites move along it
+/// at a constant speed.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Path {
+    start: Vec2d,
+    segments: Vec<PathSegment>,
+    auto_rotate: bool,
+    // every segment as a cubic curve
+    curves: Vec<[Vec2d; 4]>,
+    // the length of the path at every sample, starting with 0
+    lengths: Vec<Scalar>,
+}
+
+impl Path {
+    /// Create an empty path at `start`
+    pub fn new(start: Vec2d) -> Path {
+        Path {
+            start,
+            segments: Vec::new(),
+            auto_rotate: false,
+            curves: Vec::new(),
+            lengths: vec![0.0],
+        }
+    }
+
+    /// Create a centripetal Catmull-Rom spline through the waypoints,
+    /// as cubic Bezier curves from one waypoint to the next
+    ///
+    /// A closed spline goes back to the first waypoint smoothly.
+    pub fn catmull_rom(waypoints: &[Vec2d], closed: bool) -> Path {
+        let n = waypoints.len();
+        let mut path = Path::new(waypoints.first().cloned().unwrap_or([0.0, 0.0]));
+        if n < 2 {
+            return path;
+        }
+
+        let segments = if closed { n } else { n - 1 };
+        for i in 0..segments {
+            let p1 = waypoints[i];
+            let p2 = waypoints[(i + 1) % n];
+            // open splines mirror their ends
+            let p0 = if closed || i > 0 {
+                waypoints[(i + n - 1) % n]
+            } else {
+                [2.0 * p1[0] - p2[0], 2.0 * p1[1] - p2[1]]
+            };
+            let p3 = if closed || i + 2 < n {
+                waypoints[(i + 2) % n]
+            } else {
+                [2.0 * p2[0] - p1[0], 2.0 * p2[1] - p1[1]]
+            };
+
+            // centripetal knots are spaced by the square root of the distance
+            let (d01, d12, d23) = (
+                distance(p0, p1).sqrt(),
+                distance(p1, p2).sqrt(),
+                distance(p2, p3).sqrt(),
+            );
+            // the tangents at p1 and p2, scaled to the segment
+            let chord = [p2[0] - p1[0], p2[1] - p1[1]];
+            let m1 = if d01 > 0.0 && d12 > 0.0 {
+                let f = |k: usize| (p1[k] - p0[k]) / d01 - (p2[k] - p0[k]) / (d01 + d12);
+                [chord[0] + d12 * f(0), chord[1] + d12 * f(1)]
+            } else {
+                chord
+            };
+            let m2 = if d12 > 0.0 && d23 > 0.0 {
+                let f = |k: usize| (p3[k] - p2[k]) / d23 - (p3[k] - p1[k]) / (d12 + d23);
+                [chord[0] + d12 * f(0), chord[1] + d12 * f(1)]
+            } else {
+                chord
+            };
+            path.push(PathSegment::Cubic(
+                [p1[0] + m1[0] / 3.0, p1[1] + m1[1] / 3.0],
+                [p2[0] - m2[0] / 3.0, p2[1] - m2[1] / 3.0],
+                p2,
+            ));
+        }
+        path
+    }
+
+    /// Add a line to `end`
+    pub fn line_to(mut self, end: Vec2d) -> Path {
+        self.push(PathSegment::Line(end));
+        self
+    }
+
+    /// Add a quadratic Bezier curve to `end`
+    pub fn quad_to(mut self, control: Vec2d, end: Vec2d) -> Path {
+        self.push(PathSegment::Quadratic(control, end));
+        self
+    }
+
+    /// Add a cubic Bezier curve to `end`
+    pub fn cubic_to(mut self, control_1: Vec2d, control_2: Vec2d, end: Vec2d) -> Path {
+        self.push(PathSegment::Cubic(control_1, control_2, end));
+        self
+    }
+
+    /// Add a segment to the end of the path
+    pub fn push(&mut self, segment: PathSegment) {
+        let p0 = self.end();
+        let lerp = |a: Vec2d, b: Vec2d, f: Scalar| [a[0] + (b[0] - a[0]) * f, a[1] + (b[1] - a[1]) * f];
+        let curve = match segment {
+            PathSegment::Line(p3) => [p0, lerp(p0, p3, 1.0 / 3.0), lerp(p0, p3, 2.0 / 3.0), p3],
+            PathSegment::Quadratic(c, p3) => [p0, lerp(p0, c, 2.0 / 3.0), lerp(p3, c, 2.0 / 3.0), p3],
+            PathSegment::Cubic(c1, c2, p3) => [p0, c1, c2, p3],
+        };
+        self.segments.push(segment);
+        self.curves.push(curve);
+
+        let mut length = *self.lengths.last().unwrap();
+        let mut prev = p0;
+        for i in 1..SAMPLES + 1 {
+            let p = point(&curve, i as f64 / SAMPLES as f64);
+            length += distance(prev, p);
+            self.lengths.push(length);
+            prev = p;
+        }
+    }
+
+    /// Get the start of the path
+    #[inline(always)]
+    pub fn get_start(&self) -> Vec2d {
+        self.start
+    }
+
+    /// Get the segments of the path
+    #[inline(always)]
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Whether or not sprites turn to face the direction of the path
+    #[inline(always)]
+    pub fn get_auto_rotate(&self) -> bool {
+        self.auto_rotate
+    }
+
+    /// Turn sprites to the direction of the path, in degree from the x axis,
+    /// while they move along it. Default value is false
+    #[inline(always)]
+    pub fn set_auto_rotate(&mut self, auto_rotate: bool) {
+        self.auto_rotate = auto_rotate;
+    }
+
+    /// Get the end of the path
+    pub fn end(&self) -> Vec2d {
+        self.curves.last().map_or(self.start, |curve| curve[3])
+    }
+
+    /// Get the length of the path
+    #[inline(always)]
+    pub fn length(&self) -> Scalar {
+        *self.lengths.last().unwrap()
+    }
+
+    /// Get the point and direction `u` of the way along the path,
+    /// from 0 to 1, at a constant speed
+    ///
+    /// The direction is None where the path does not move.
+    pub fn sample(&self, u: f64) -> (Vec2d, Option<Vec2d>) {
+        if self.curves.is_empty() {
+            return (self.start, None);
+        }
+        self.sample_length(u.clamp(0.0, 1.0) * self.length())
+    }
+
+    /// Get the point and direction `u` of the way along the path,
+    /// from 0 to 1, where every segment takes the same time
+    pub fn sample_segments(&self, u: f64) -> (Vec2d, Option<Vec2d>) {
+        if self.curves.is_empty() {
+            return (self.start, None);
+        }
+        let n = self.curves.len();
+        let x = u.clamp(0.0, 1.0) * n as f64;
+        let i = (x as usize).min(n - 1);
+        let (l0, l1) = (self.lengths[i * SAMPLES], self.lengths[(i + 1) * SAMPLES]);
+        self.sample_length(l0 + (l1 - l0) * (x - i as f64))
+    }
+
+    // The point and direction at `target` along the path
+    fn sample_length(&self, target: Scalar) -> (Vec2d, Option<Vec2d>) {
+        let i = self.lengths.partition_point(|&l| l < target).clamp(1, self.lengths.len() - 1);
+        let (l0, l1) = (self.lengths[i - 1], self.lengths[i]);
+        let f = if l1 > l0 { (target - l0) / (l1 - l0) } else { 0.0 };
+        let curve = (i - 1) / SAMPLES;
+        let t = (((i - 1) % SAMPLES) as f64 + f) / SAMPLES as f64;
+        self.sample_curve(curve, t)
+    }
+
+    // The point and direction of a curve at `t`, from 0 to 1
+    fn sample_curve(&self, i: usize, t: f64) -> (Vec2d, Option<Vec2d>) {
+        let curve = &self.curves[i];
+        let mut tangent = derivative(curve, t);
+        if tangent == [0.0, 0.0] {
+            tangent = [curve[3][0] - curve[0][0], curve[3][1] - curve[0][1]];
+        }
+        (point(curve, t), if tangent == [0.0, 0.0] { None } else { Some(tangent) })
+    }
+}
+
+fn point(c: &[Vec2d; 4], t: f64) -> Vec2d {
+    let s = 1.0 - t;
+    let w = [s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t];
+    [
+        w[0] * c[0][0] + w[1] * c[1][0] + w[2] * c[2][0] + w[3] * c[3][0],
+        w[0] * c[0][1] + w[1] * c[1][1] + w[2] * c[2][1] + w[3] * c[3][1],
+    ]
+}
+
+fn derivative(c: &[Vec2d; 4], t: f64) -> Vec2d {
+    let s = 1.0 - t;
+    let w = [3.0 * s * s, 6.0 * s * t, 3.0 * t * t];
+    [
+        w[0] * (c[1][0] - c[0][0]) + w[1] * (c[2][0] - c[1][0]) + w[2] * (c[3][0] - c[2][0]),
+        w[0] * (c[1][1] - c[0][1]) + w[1] * (c[2][1] - c[1][1]) + w[2] * (c[3][1] - c[2][1]),
+    ]
+}
+
+fn distance(a: Vec2d, b: Vec2d) -> Scalar {
+    ((b[0] - a[0]) * (b[0] - a[0]) + (b[1] - a[1]) * (b[1] - a[1])).sqrt()
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use graphics::ImageSize;
+use graphics::math::{ Matrix2d, Vec2d, identity, invert, transform_pos };
+use input::MouseButton;
+
+use clip::Clip;
+use sprite::Sprite;
+
+/// What happened to the pointer
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PointerEventKind {
+    /// A button was pressed over the sprite
+    Press(MouseButton),
+    /// A button was released over the sprite
+    Release(MouseButton),
+    /// A button was pressed and released over the same sprite
+    Click(MouseButton),
+    /// The pointer moved over the sprite
+    Move,
+    /// The pointer entered the sprite or one of its children,
+    /// it does not bubble
+    Enter,
+    /// The pointer left the sprite and its children,
+    /// it does not bubble
+    Leave,
+    /// The draggable sprite started following the pointer,
+    /// it does not bubble
+    DragStart,
+    /// The dragged sprite moved, it does not bubble
+    DragMove,
+    /// The dragged sprite was released over the drop target in `related`,
+    /// if any, it does not bubble
+    DragEnd,
+    /// The sprite in `related` was dropped on this drop target,
+    /// it does not bubble
+    Drop,
+}
+
+/// The directions a draggable sprite can move in, in its parent's space
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DragAxis {
+    /// Follow the pointer freely
+    Both,
+    /// Only move along the x axis
+    Horizontal,
+    /// Only move along the y axis
+    Vertical,
+}
+
+/// A pointer event sent to the handlers registered with `Scene::on_pointer`
+///
+/// The event is first sent to the top most sprite under the pointer,
+/// then bubbles up its parents until a handler consumes it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PointerEvent {
+    /// What happened
+    pub kind: PointerEventKind,
+    /// The sprite under the pointer
+    pub target: Uuid,
+    /// The sprite whose handler is called
+    pub current: Uuid,
+    /// The position of the pointer in the window
+    pub position: Vec2d,
+    /// The position of the pointer in the space of `current`,
+    /// where its anchor point is the origin
+    pub local_position: Vec2d,
+    /// The other sprite of drag and drop events
+    pub related: Option<Uuid>,
+    consumed: bool,
+}
+
+impl PointerEvent {
+    pub(crate) fn new(kind: PointerEventKind, target: Uuid, position: Vec2d) -> PointerEvent {
+        PointerEvent {
+            kind,
+            target,
+            current: target,
+            position,
+            local_position: position,
+            related: None,
+            consumed: false,
+        }
+    }
+
+    /// Stop the event from bubbling to the parents of `current`
+    #[inline(always)]
+    pub fn consume(&mut self) {
+        self.consumed = true;
+    }
+
+    /// Whether or not a handler consumed the event
+    #[inline(always)]
+    pub fn is_consumed(&self) -> bool {
+        self.consumed
+    }
+}
+
+/// A handler of pointer events
+pub type PointerHandler = Box<dyn FnMut(&mut PointerEvent)>;
+
+// What the scene remembers about the pointer between events
+#[derive(Default)]
+pub(crate) struct PointerState {
+    pub position: Option<Vec2d>,
+    // the sprites under the pointer, deepest first, with their transforms
+    pub hovered: Vec<(Uuid, Matrix2d)>,
+    // the sprite each button was pressed over
+    pub pressed: HashMap<MouseButton, Uuid>,
+    pub drag: Option<Drag>,
+}
+
+// A draggable sprite grabbed by the left button
+#[derive(Clone, Copy)]
+pub(crate) struct Drag {
+    pub id: Uuid,
+    pub axis: DragAxis,
+    // the transform of the parent's space
+    pub parent: Matrix2d,
+    // the sprite's position minus the pointer's, in the parent's space
+    pub offset: Vec2d,
+    pub start: Vec2d,
+    // the position of the pointer in the window when it was pressed
+    pub press: Vec2d,
+    pub started: bool,
+}
+
+// The sprites under `point`, top most first, with the transform of their
+// children. Returns the path from the hit sprite up to the root of `sprites`.
+// The subtree of `skip` and children clipped away are ignored.
+pub(crate) fn hit_path<I: ImageSize>(
+    sprites: &[Sprite<I>],
+    t: Matrix2d,
+    point: Vec2d,
+    skip: Option<Uuid>
+) -> Option<Vec<(Uuid, Matrix2d)>> {
+    for sprite in sprites.iter().rev() {
+        if !sprite.get_visible() || Some(sprite.id()) == skip {
+            continue;
+        }
+        let (transformed, model, rect) = sprite.draw_params(t);
+        // children outside the clip of their parent are hidden
+        let clipped = match sprite.get_clip() {
+            Some(Clip::Rect(c)) => {
+                !contains(transformed, [rect[0] + c[0], rect[1] + c[1], c[2], c[3]], point)
+            },
+            Some(Clip::Content) => !contains(model, rect, point),
+            Some(Clip::Scissor(c)) => {
+                let c = [c[0] as f64, c[1] as f64, c[2] as f64, c[3] as f64];
+                !contains(identity(), c, point)
+            },
+            None => false,
+        };
+        if !clipped {
+            if let Some(mut path) = hit_path(sprite.children(), transformed, point, skip) {
+                path.push((sprite.id(), transformed));
+                return Some(path);
+            }
+        }
+        if contains(model, rect, point) {
+            return Some(vec![(sprite.id(), transformed)]);
+        }
+    }
+    None
+}
+
+// The transform of the space `id` is positioned in, if it is `sprite` placed
+// by `t` or one of its children
+pub(crate) fn parent_transform<I: ImageSize>(
+    sprite: &Sprite<I>,
+    t: Matrix2d,
+    id: Uuid
+) -> Option<Matrix2d> {
+    if sprite.id() == id {
+        return Some(t);
+    }
+    let transformed = sprite.transform(t);
+    sprite.children().iter().find_map(|child| parent_transform(child, transformed, id))
+}
+
+// Whether or not `point` is in `rect` transformed by `t`
+fn contains(t: Matrix2d, rect: [f64; 4], point: Vec2d) -> bool {
+    let det = t[0][0] * t[1][1] - t[0][1] * t[1][0];
+    if det == 0.0 || rect[2] <= 0.0 || rect[3] <= 0.0 {
+        return false;
+    }
+    let p = local(t, point);
+    p[0] >= rect[0] && p[0] < rect[0] + rect[2] &&
+    p[1] >= rect[1] && p[1] < rect[1] + rect[3]
+}
+
+// `point` in the space of `t`
+pub(crate) fn local(t: Matrix2d, point: Vec2d) -> Vec2d {
+    transform_pos(invert(t), point)
+}
@@ -1,32 +1,96 @@
+use std::cmp::Ordering;
 use std::collections::{HashSet,HashMap};
 use std::mem;
+use std::slice;
+use std::vec;
 use uuid::Uuid;
 
-use graphics::{ Graphics, ImageSize };
-use graphics::math::{ Matrix2d };
+use graphics::{ Graphics, ImageSize, Transformed };
+use graphics::character::CharacterCache;
+use graphics::math::{ self, Scalar, Matrix2d, Vec2d };
 
-use input::GenericEvent;
+use input::{ Button, Event, GenericEvent, MouseButton, Touch, UpdateArgs, UpdateEvent };
 use ai_behavior::{
     Behavior,
     State,
+    Status,
     Running,
+    Success,
 };
 
-use sprite::Sprite;
+use batch::SpriteBatch;
+use camera::Camera;
+use collision::{ has_colliders, world_colliders };
+use debug::DebugDraw;
+use layer::{ Layer, DEFAULT_LAYER };
+use pointer::{ self, Drag, DragAxis, PointerEvent, PointerEventKind, PointerHandler, PointerState };
+use sprite::{ AnimatedProperties, Sprite, SpriteSnapshot };
 
 use animation::{
     Animation,
     AnimationState,
 };
 
+/// A handle to an animation started by `Scene::run`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct AnimationId(u64);
+
+struct RunningAnimation {
+    id: AnimationId,
+    behavior: Behavior<Animation>,
+    state: State<Animation, AnimationState>,
+    paused: bool,
+    // the time the animation ran for
+    elapsed: f64,
+    // what the animation changes before its first update, to seek from
+    start: Option<SpriteSnapshot>,
+    // the status of an animation which reached its end by seeking
+    done: Option<Status>,
+}
+
+/// An animation of the scene finished, see `Scene::drain_events`
+#[derive(Clone, PartialEq)]
+pub struct AnimationEvent {
+    /// The handle returned by `Scene::run`
+    pub id: AnimationId,
+    /// The sprite the animation ran on
+    pub sprite: Uuid,
+    /// The behavior given to `Scene::run`
+    pub behavior: Behavior<Animation>,
+    /// How the behavior finished, `Success` or `Failure`
+    pub status: Status,
+}
+
+/// A handler of finished animations
+pub type AnimationHandler = Box<dyn FnMut(&AnimationEvent)>;
+
 /// A scene is used to manage sprite's life and run animation with sprite
 pub struct Scene<I: ImageSize> {
     children: Vec<Sprite<I>>,
     children_index: HashMap<Uuid, usize>,
-    running: HashMap<Uuid,
-        Vec<(Behavior<Animation>, State<Animation, AnimationState>, bool)>>,
+    // Sorted by draw order
+    layers: Vec<Layer>,
+    running: HashMap<Uuid, Vec<RunningAnimation>>,
+    // The sprite of every running animation
+    animation_sprites: HashMap<AnimationId, Uuid>,
+    next_animation_id: u64,
     // Set of sprites that should be removed once animations have finished.
     dead_sprites: HashSet<Uuid>,
+    camera: Camera,
+    time_scale: f64,
+    debug_draw: DebugDraw,
+    collisions: Vec<(Uuid, Uuid)>,
+    pointer: PointerState,
+    drag_threshold: Scalar,
+    pointer_handlers: HashMap<Uuid, Vec<PointerHandler>>,
+    events: Vec<AnimationEvent>,
+    on_finished: Option<AnimationHandler>,
+}
+
+impl<I: ImageSize> Default for Scene<I> {
+    fn default() -> Scene<I> {
+        Scene::new()
+    }
 }
 
 impl<I: ImageSize> Scene<I> {
@@ -35,45 +99,95 @@ impl<I: ImageSize> Scene<I> {
         Scene {
             children: Vec::new(),
             children_index: HashMap::new(),
+            layers: vec![Layer::new(DEFAULT_LAYER, 0)],
             running: HashMap::new(),
+            animation_sprites: HashMap::new(),
+            next_animation_id: 0,
             dead_sprites: HashSet::new(),
+            camera: Camera::new(),
+            time_scale: 1.0,
+            debug_draw: DebugDraw::default(),
+            collisions: Vec::new(),
+            pointer: PointerState::default(),
+            drag_threshold: 4.0,
+            pointer_handlers: HashMap::new(),
+            events: Vec::new(),
+            on_finished: None,
         }
     }
 
-    /// Update animation's state
+    /// Update animation's state, and send mouse and touch events to the
+    /// pointer handlers of the sprites under the pointer
+    ///
+    /// Touch positions are scaled by the camera's viewport size, touch events
+    /// are ignored until it is set.
     pub fn event<E>(&mut self, e: &E) where E: GenericEvent {
+        self.dispatch_pointer(e);
+
+        // the camera settles in real time, even when the scene is paused
+        if let Some(dt) = e.update(|args| args.dt) {
+            self.camera.update(dt);
+        }
+
+        let dt = e.update(|args| args.dt * self.time_scale);
+        let mut time_scales = HashMap::new();
+        if let Some(dt) = dt {
+            for child in &mut self.children {
+                child.update(dt);
+            }
+            // animations only need their own update time when it is scaled
+            let scaled = self.time_scale != 1.0 || has_time_scales(&self.children);
+            if scaled && !self.running.is_empty() {
+                sprite_time_scales(&self.children, self.time_scale, &mut time_scales);
+            }
+        }
+
         // regenerate the animations and their states
         let running = mem::take(&mut self.running);
 
         for (id, animations) in running.into_iter() {
             let mut new_animations = Vec::new();
 
-            for (b, mut a, paused) in animations.into_iter() {
-                if paused {
-                    new_animations.push((b, a, paused));
+            // animations get the update time of their sprite
+            let scaled = match (dt, time_scales.get(&id)) {
+                (Some(_), Some(&scale)) if scale != 1.0 => e.update(|args| args.dt * scale)
+                    .and_then(|dt| UpdateEvent::from_dt(dt, e)),
+                _ => None,
+            };
+            let e = scaled.as_ref().unwrap_or(e);
+
+            for mut animation in animations.into_iter() {
+                if animation.paused {
+                    new_animations.push(animation);
                     continue;
                 }
 
-                let sprite = self.child_mut(id).unwrap();
-                let (status, _) = a.event(e, &mut |args| {
-                    let (state, status, remain) = {
-                        let start_state;
-                        let state = match *args.state {
-                            None => { start_state = args.action.to_state(sprite); &start_state },
-                            Some(ref state) => state,
-                        };
-                        state.update(sprite, args.dt)
-                    };
-                    *args.state = state;
-                    (status, remain)
-                });
-
-                match status {
-                    // the behavior is still running, add it for next update
-                    Running => {
-                        new_animations.push((b, a, paused));
-                    },
-                    _ => {},
+                let status = match animation.done {
+                    // seeked to its end, it finishes on the next update
+                    Some(status) if dt.is_some() => status,
+                    Some(_) => Running,
+                    None => {
+                        animation.elapsed += e.update(|args| args.dt).unwrap_or(0.0);
+                        let sprite = find_child_mut(&mut self.children, &self.children_index, id)
+                            .unwrap();
+                        if animation.start.is_none() {
+                            animation.start = Some(snapshot(sprite, &animation.behavior));
+                        }
+                        step(&mut animation.state, sprite, &mut self.camera, e)
+                    }
+                };
+
+                // the behavior is still running, add it for next update
+                if status == Running {
+                    new_animations.push(animation);
+                } else {
+                    self.animation_sprites.remove(&animation.id);
+                    self.finished(AnimationEvent {
+                        id: animation.id,
+                        sprite: id,
+                        behavior: animation.behavior,
+                        status,
+                    });
                 }
             }
 
@@ -82,9 +196,377 @@ impl<I: ImageSize> Scene<I> {
             }
         }
 
+        if e.update(|_| ()).is_some() {
+            self.update_collisions();
+        }
+
         self.prune_dead_sprites();
     }
 
+    fn update_collisions(&mut self) {
+        self.collisions.clear();
+        if !has_colliders(&self.children) {
+            return;
+        }
+
+        let mut colliders = Vec::new();
+        world_colliders(&self.children, math::identity(), &mut colliders);
+        for (i, &(a, collider_a, ref shape_a)) in colliders.iter().enumerate() {
+            for &(b, collider_b, ref shape_b) in &colliders[i + 1..] {
+                if collider_a.can_collide(collider_b) && shape_a.overlaps(shape_b) {
+                    self.collisions.push((a, b));
+                }
+            }
+        }
+    }
+
+    /// Get the pairs of sprites whose colliders overlapped at the last update,
+    /// as allowed by their layer masks
+    #[inline(always)]
+    pub fn collisions(&self) -> &[(Uuid, Uuid)] {
+        &self.collisions
+    }
+
+    /// Whether or not the colliders of two sprites overlap now,
+    /// whatever their layer masks
+    ///
+    /// Colliders are in world space, where the scene's sprites are
+    /// positioned before any layer or camera transform.
+    pub fn overlaps(&self, a: Uuid, b: Uuid) -> bool {
+        let mut colliders = Vec::new();
+        world_colliders(&self.children, math::identity(), &mut colliders);
+        let shape_a = colliders.iter().find(|c| c.0 == a);
+        let shape_b = colliders.iter().find(|c| c.0 == b);
+        match (shape_a, shape_b) {
+            (Some(a), Some(b)) => a.2.overlaps(&b.2),
+            _ => false,
+        }
+    }
+
+    /// Call `handler` with the pointer events of a sprite,
+    /// and the ones bubbling up from its children
+    pub fn on_pointer<F>(&mut self, id: Uuid, handler: F)
+        where F: FnMut(&mut PointerEvent) + 'static
+    {
+        self.pointer_handlers.entry(id).or_default().push(Box::new(handler));
+    }
+
+    /// Remove the pointer handlers of a sprite
+    pub fn clear_pointer_handlers(&mut self, id: Uuid) {
+        self.pointer_handlers.remove(&id);
+    }
+
+    /// Get how far the pointer moves before a draggable sprite follows it
+    #[inline(always)]
+    pub fn get_drag_threshold(&self) -> Scalar {
+        self.drag_threshold
+    }
+
+    /// Set how far the pointer moves, in window pixels, before a draggable
+    /// sprite follows it. Smaller moves still click. Default value is 4
+    #[inline(always)]
+    pub fn set_drag_threshold(&mut self, threshold: Scalar) {
+        self.drag_threshold = threshold;
+    }
+
+    /// Find the top most visible sprite at `pos` in the window
+    pub fn hit_test(&self, pos: Vec2d) -> Option<Uuid> {
+        self.hit_path(pos, None).first().map(|&(id, _)| id)
+    }
+
+    // The top most sprite at `pos` and its parents, with their transforms,
+    // ignoring the subtree of `skip`
+    fn hit_path(&self, pos: Vec2d, skip: Option<Uuid>) -> Vec<(Uuid, Matrix2d)> {
+        for layer in self.layers.iter().rev() {
+            if !layer.get_visible() {
+                continue;
+            }
+
+            for lt in self.layer_transforms(layer, math::identity()).into_iter().rev() {
+                for id in layer.children().iter().rev() {
+                    let sprite = &self.children[self.children_index[id]];
+                    if let Some(path) = pointer::hit_path(slice::from_ref(sprite), lt, pos, skip) {
+                        return path;
+                    }
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    fn dispatch_pointer<E: GenericEvent>(&mut self, e: &E) {
+        if let Some(pos) = e.mouse_cursor_args() {
+            self.pointer_moved(pos);
+        }
+        if let Some(Button::Mouse(button)) = e.press_args() {
+            self.pointer_pressed(button);
+        }
+        if let Some(Button::Mouse(button)) = e.release_args() {
+            self.pointer_released(button);
+        }
+        if let Some(false) = e.cursor_args() {
+            self.pointer_left();
+        }
+
+        // touches are normalized, they act as the left button over the viewport
+        if let Some(args) = e.touch_args() {
+            let (vw, vh) = self.camera.get_viewport_size();
+            if vw <= 0.0 || vh <= 0.0 {
+                return;
+            }
+            let pos = [args.position()[0] * vw, args.position()[1] * vh];
+            match args.touch {
+                Touch::Start => {
+                    self.pointer_moved(pos);
+                    self.pointer_pressed(MouseButton::Left);
+                },
+                Touch::Move => self.pointer_moved(pos),
+                Touch::End => {
+                    self.pointer_moved(pos);
+                    self.pointer_released(MouseButton::Left);
+                    self.pointer_left();
+                },
+                Touch::Cancel => {
+                    self.pointer.pressed.clear();
+                    if let Some(drag) = self.pointer.drag.take() {
+                        self.end_drag(drag, None);
+                    }
+                    self.pointer_left();
+                },
+            }
+        }
+    }
+
+    fn pointer_moved(&mut self, pos: Vec2d) {
+        self.pointer.position = Some(pos);
+        let path = self.hit_path(pos, None);
+        self.update_hover(&path, pos);
+        if !path.is_empty() {
+            self.send_pointer(PointerEventKind::Move, &path, pos, true);
+        }
+        if let Some(drag) = self.pointer.drag {
+            self.drag_to(drag, pos);
+        }
+    }
+
+    fn pointer_left(&mut self) {
+        if let Some(pos) = self.pointer.position.take() {
+            self.update_hover(&[], pos);
+        }
+    }
+
+    fn pointer_pressed(&mut self, button: MouseButton) {
+        let pos = match self.pointer.position {
+            Some(pos) => pos,
+            None => return,
+        };
+        let path = self.hit_path(pos, None);
+        match path.first() {
+            Some(&(target, _)) => self.pointer.pressed.insert(button, target),
+            None => self.pointer.pressed.remove(&button),
+        };
+        if path.is_empty() {
+            return;
+        }
+        self.send_pointer(PointerEventKind::Press(button), &path, pos, true);
+
+        // grab the deepest draggable sprite under the pointer
+        if button != MouseButton::Left {
+            return;
+        }
+        for &(id, t) in &path {
+            let sprite = match self.child(id) {
+                Some(sprite) if sprite.get_draggable() => sprite,
+                _ => continue,
+            };
+            let (local, _, _) = sprite.draw_params(math::identity());
+            let parent = math::multiply(t, math::invert(local));
+            let (x, y) = sprite.get_position();
+            let p = pointer::local(parent, pos);
+            self.pointer.drag = Some(Drag {
+                id,
+                axis: sprite.get_drag_axis(),
+                parent,
+                offset: [x - p[0], y - p[1]],
+                start: [x, y],
+                press: pos,
+                started: false,
+            });
+            break;
+        }
+    }
+
+    fn pointer_released(&mut self, button: MouseButton) {
+        let pressed = self.pointer.pressed.remove(&button);
+        let drag = if button == MouseButton::Left { self.pointer.drag.take() } else { None };
+        let pos = match self.pointer.position {
+            Some(pos) => pos,
+            None => return,
+        };
+
+        let path = self.hit_path(pos, None);
+        if !path.is_empty() {
+            self.send_pointer(PointerEventKind::Release(button), &path, pos, true);
+        }
+
+        match drag {
+            Some(drag) if drag.started => {
+                let target = self.hit_path(pos, Some(drag.id)).into_iter()
+                    .find(|&(id, _)| self.child(id).is_some_and(|s| s.get_drop_target()));
+                self.end_drag(drag, target);
+            },
+            _ => {
+                if !path.is_empty() && pressed == Some(path[0].0) {
+                    self.send_pointer(PointerEventKind::Click(button), &path, pos, true);
+                }
+            },
+        }
+    }
+
+    // Move the dragged sprite to follow the pointer in its parent's space
+    fn drag_to(&mut self, mut drag: Drag, pos: Vec2d) {
+        if !drag.started {
+            let (dx, dy) = (pos[0] - drag.press[0], pos[1] - drag.press[1]);
+            if (dx * dx + dy * dy).sqrt() < self.drag_threshold {
+                return;
+            }
+        }
+        // the camera, a layer or the parent may have moved since the press
+        drag.parent = self.parent_transform(drag.id, drag.parent).unwrap_or(drag.parent);
+        self.pointer.drag = Some(drag);
+
+        let p = pointer::local(drag.parent, pos);
+        let mut position = [p[0] + drag.offset[0], p[1] + drag.offset[1]];
+        match drag.axis {
+            DragAxis::Both => {},
+            DragAxis::Horizontal => position[1] = drag.start[1],
+            DragAxis::Vertical => position[0] = drag.start[0],
+        }
+        let local = match self.child_mut(drag.id) {
+            Some(sprite) => {
+                sprite.set_position(position[0], position[1]);
+                sprite.draw_params(math::identity()).0
+            },
+            None => {
+                self.pointer.drag = None;
+                return;
+            },
+        };
+
+        let entry = [(drag.id, math::multiply(drag.parent, local))];
+        if !drag.started {
+            drag.started = true;
+            self.pointer.drag = Some(drag);
+            self.send_pointer(PointerEventKind::DragStart, &entry, pos, false);
+        }
+        self.send_pointer(PointerEventKind::DragMove, &entry, pos, false);
+    }
+
+    // Tell the dragged sprite and the drop target the drag is over
+    fn end_drag(&mut self, mut drag: Drag, target: Option<(Uuid, Matrix2d)>) {
+        if !drag.started {
+            return;
+        }
+        drag.parent = self.parent_transform(drag.id, drag.parent).unwrap_or(drag.parent);
+        let pos = self.pointer.position.unwrap_or([0.0, 0.0]);
+        let local = match self.child(drag.id) {
+            Some(sprite) => sprite.draw_params(math::identity()).0,
+            None => return,
+        };
+
+        let mut event = PointerEvent::new(PointerEventKind::DragEnd, drag.id, pos);
+        event.related = target.map(|(id, _)| id);
+        self.send_event(event, &[(drag.id, math::multiply(drag.parent, local))], false);
+        if let Some(target) = target {
+            let mut event = PointerEvent::new(PointerEventKind::Drop, target.0, pos);
+            event.related = Some(drag.id);
+            self.send_event(event, &[target], false);
+        }
+    }
+
+    // The transform of the space a sprite is positioned in, in the copy of a
+    // wrapping layer closest to `near`
+    fn parent_transform(&self, id: Uuid, near: Matrix2d) -> Option<Matrix2d> {
+        let distance = |m: &Matrix2d| {
+            (m[0][2] - near[0][2]) * (m[0][2] - near[0][2]) +
+            (m[1][2] - near[1][2]) * (m[1][2] - near[1][2])
+        };
+        for layer in &self.layers {
+            for root in layer.children() {
+                let sprite = &self.children[self.children_index[root]];
+                if sprite.id() != id && sprite.child(id).is_none() {
+                    continue;
+                }
+                return self.layer_transforms(layer, math::identity()).into_iter()
+                    .filter_map(|lt| pointer::parent_transform(sprite, lt, id))
+                    .min_by(|a, b| distance(a).partial_cmp(&distance(b)).unwrap_or(Ordering::Equal));
+            }
+        }
+        None
+    }
+
+    // Send leave events to the sprites no longer under the pointer, deepest
+    // first, and enter events to the new ones, parents first
+    fn update_hover(&mut self, path: &[(Uuid, Matrix2d)], pos: Vec2d) {
+        let old = mem::replace(&mut self.pointer.hovered, path.to_vec());
+        for entry in &old {
+            if !path.iter().any(|&(id, _)| id == entry.0) {
+                self.send_pointer(PointerEventKind::Leave, slice::from_ref(entry), pos, false);
+            }
+        }
+        for entry in path.iter().rev() {
+            if !old.iter().any(|&(id, _)| id == entry.0) {
+                self.send_pointer(PointerEventKind::Enter, slice::from_ref(entry), pos, false);
+            }
+        }
+    }
+
+    fn send_pointer(
+        &mut self,
+        kind: PointerEventKind,
+        path: &[(Uuid, Matrix2d)],
+        pos: Vec2d,
+        bubble: bool
+    ) {
+        self.send_event(PointerEvent::new(kind, path[0].0, pos), path, bubble);
+    }
+
+    fn send_event(&mut self, mut event: PointerEvent, path: &[(Uuid, Matrix2d)], bubble: bool) {
+        for &(id, t) in path {
+            event.current = id;
+            event.local_position = pointer::local(t, event.position);
+            if let Some(handlers) = self.pointer_handlers.get_mut(&id) {
+                for handler in handlers.iter_mut() {
+                    handler(&mut event);
+                }
+            }
+            if event.is_consumed() || !bubble {
+                break;
+            }
+        }
+    }
+
+    fn finished(&mut self, event: AnimationEvent) {
+        match self.on_finished {
+            Some(ref mut handler) => handler(&event),
+            None => self.events.push(event),
+        }
+    }
+
+    /// Take the events of the animations which finished since the last call
+    ///
+    /// Events are queued until drained, unless a handler is set with
+    /// `on_animation_finished`.
+    pub fn drain_events(&mut self) -> vec::Drain<'_, AnimationEvent> {
+        self.events.drain(..)
+    }
+
+    /// Call `handler` when an animation finishes instead of queuing an event,
+    /// `None` to queue events again
+    pub fn on_animation_finished(&mut self, handler: Option<AnimationHandler>) {
+        self.on_finished = handler;
+    }
+
     fn prune_dead_sprites(&mut self) {
         if !self.dead_sprites.is_empty() {
             let mut to_remove = HashSet::new();
@@ -104,87 +586,444 @@ impl<I: ImageSize> Scene<I> {
         }
     }
 
-    /// Render this scene
+    /// Render this scene layer by layer
+    ///
+    /// World layers are drawn through the camera, scrolled by their parallax
+    /// factors and repeated if they wrap around. Screen space layers are drawn
+    /// with the view transform `t` as is.
     pub fn draw<B: Graphics<Texture = I>>(&self, t: Matrix2d, b: &mut B) {
-        for child in &self.children {
-            child.draw(t, b);
+        for layer in &self.layers {
+            if !layer.get_visible() {
+                continue;
+            }
+
+            for lt in self.layer_transforms(layer, t) {
+                for id in layer.children() {
+                    self.children[self.children_index[id]].draw(lt, b);
+                }
+            }
         }
     }
 
     /// Render this scene with tint
     pub fn draw_tinted<B: Graphics<Texture = I>>(&self, t: Matrix2d, b: &mut B, c: [f32;3]) {
-        for child in &self.children {
-            child.draw_tinted(t,b,c)
+        for layer in &self.layers {
+            if !layer.get_visible() {
+                continue;
+            }
+
+            for lt in self.layer_transforms(layer, t) {
+                for id in layer.children() {
+                    self.children[self.children_index[id]].draw_tinted(lt, b, c);
+                }
+            }
         }
     }
 
-    /// Register animation with sprite
-    pub fn run(&mut self, sprite_id: Uuid, animation: &Behavior<Animation>) {
-        use std::collections::hash_map::Entry::{ Vacant, Occupied };
-        let animations = match self.running.entry(sprite_id) {
-            Vacant(entry) => entry.insert(Vec::new()),
-            Occupied(entry) => entry.into_mut()
-        };
-        let state = State::new(animation.clone());
-        animations.push((animation.clone(), state, false));
+    /// Render this scene like `draw`, but group the sprites by texture to
+    /// make fewer draw calls. Overlapping sprites keep their order,
+    /// so the result looks the same as `draw`.
+    ///
+    /// Labels are not batched, they are drawn on their own in between batches.
+    /// Bitmap text is batched with the other quads.
+    pub fn draw_batched<B: Graphics<Texture = I>>(&self, t: Matrix2d, b: &mut B) {
+        let mut batch = SpriteBatch::new();
+        for layer in &self.layers {
+            if !layer.get_visible() {
+                continue;
+            }
+
+            for lt in self.layer_transforms(layer, t) {
+                for id in layer.children() {
+                    self.children[self.children_index[id]].draw_batched(lt, &mut batch, b);
+                }
+            }
+        }
+        batch.draw(b);
     }
 
-    fn find(&self, sprite_id: Uuid, animation: &Behavior<Animation>) -> Option<usize> {
-        let mut index = None;
-        if let Some(animations) = self.running.get(&sprite_id) {
-            for (i, &(ref b, _, _)) in animations.iter().enumerate() {
-                if b == animation {
-                    index = Some(i);
-                    break;
+    /// Render the debug overlay of this scene: oriented bounds, anchor points
+    /// and links between parents and children, for the sprites selected by
+    /// `debug_draw_mut().all` or `Sprite::set_debug`
+    pub fn draw_debug<B: Graphics<Texture = I>>(&self, t: Matrix2d, b: &mut B) {
+        for layer in &self.layers {
+            if !layer.get_visible() {
+                continue;
+            }
+
+            for m in self.layer_transforms(layer, math::identity()) {
+                for id in layer.children() {
+                    self.children[self.children_index[id]].draw_debug(t, m, &self.debug_draw, b);
                 }
             }
         }
-        index
+    }
+
+    /// Render the names of the sprites selected for the debug overlay
+    pub fn draw_debug_names<C, B>(&self, t: Matrix2d, glyphs: &mut C, b: &mut B)
+        -> Result<(), C::Error>
+        where C: CharacterCache<Texture = I>,
+              B: Graphics<Texture = I>
+    {
+        if !self.debug_draw.names {
+            return Ok(());
+        }
+
+        for layer in &self.layers {
+            if !layer.get_visible() {
+                continue;
+            }
+
+            for m in self.layer_transforms(layer, math::identity()) {
+                for id in layer.children() {
+                    self.children[self.children_index[id]]
+                        .draw_debug_names(t, m, &self.debug_draw, glyphs, b)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the options of the debug overlay
+    #[inline(always)]
+    pub fn debug_draw(&self) -> &DebugDraw {
+        &self.debug_draw
+    }
+
+    /// Get the options of the debug overlay, mutability
+    #[inline(always)]
+    pub fn debug_draw_mut(&mut self) -> &mut DebugDraw {
+        &mut self.debug_draw
+    }
+
+    // One transform per copy of the layer to draw
+    fn layer_transforms(&self, layer: &Layer, t: Matrix2d) -> Vec<Matrix2d> {
+        if layer.get_screen_space() {
+            return vec![t.append_transform(layer.get_transform())];
+        }
+
+        let (cx, cy) = self.camera.get_position();
+        let (px, py) = layer.get_parallax();
+        let (vw, vh) = self.camera.get_viewport_size();
+        let (wrap_x, wrap_y) = layer.get_wrap();
+        let (xs, ox) = wrap_offsets(cx * px, wrap_x, vw);
+        let (ys, oy) = wrap_offsets(cy * py, wrap_y, vh);
+
+        let view = self.camera.effects_transform(t).trans(-ox, -oy);
+        let mut transforms = Vec::with_capacity(xs.len() * ys.len());
+        for y in &ys {
+            for x in &xs {
+                transforms.push(view.trans(*x, *y).append_transform(layer.get_transform()));
+            }
+        }
+        transforms
+    }
+
+    /// Add a layer, or change the draw order of the layer if it already exists
+    pub fn add_layer(&mut self, name: &str, order: i32) -> &mut Layer {
+        match self.layer_index(name) {
+            Some(_) => self.set_layer_order(name, order),
+            None => {
+                self.layers.push(Layer::new(name, order));
+                self.sort_layers();
+            },
+        }
+        self.layer_mut(name).unwrap()
+    }
+
+    /// Remove a layer and all of its sprites, returning the removed sprites.
+    /// The default layer can not be removed.
+    pub fn remove_layer(&mut self, name: &str) -> Option<Vec<Sprite<I>>> {
+        if name == DEFAULT_LAYER {
+            return None;
+        }
+
+        let index = self.layer_index(name)?;
+        let ids = self.layers[index].children().to_vec();
+        let mut removed = Vec::new();
+        for id in ids {
+            if let Some(sprite) = self.remove_child(id) {
+                removed.push(sprite);
+            }
+        }
+        self.layers.remove(index);
+        Some(removed)
+    }
+
+    /// Change the draw order of a layer
+    pub fn set_layer_order(&mut self, name: &str, order: i32) {
+        if let Some(index) = self.layer_index(name) {
+            self.layers[index].set_order(order);
+            self.sort_layers();
+        }
+    }
+
+    fn sort_layers(&mut self) {
+        // stable, layers with the same order keep the order they were added
+        self.layers.sort_by_key(|layer| layer.get_order());
+    }
+
+    fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.get_name() == name)
+    }
+
+    /// Find the layer by `name`
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|layer| layer.get_name() == name)
+    }
+
+    /// Find the layer by `name`, mutability
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|layer| layer.get_name() == name)
+    }
+
+    /// Get the scene's layers, in draw order
+    #[inline(always)]
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Get the scene's camera
+    #[inline(always)]
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Get the scene's camera, mutability
+    #[inline(always)]
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    /// Get the scene's time scale
+    #[inline(always)]
+    pub fn get_time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Set the scene's time scale, it multiplies the time of every sprite
+    /// and animation, on top of the sprites' own time scale. Camera shake
+    /// and zoom punch are not scaled, so they settle during a hit-stop.
+    /// Default value is 1
+    #[inline(always)]
+    pub fn set_time_scale(&mut self, time_scale: f64) {
+        self.time_scale = time_scale;
+    }
+
+    /// Register animation with sprite
+    ///
+    /// # Panics
+    /// If a `Reverse` or `Yoyo` animation does not wrap a tween.
+    pub fn run(&mut self, sprite_id: Uuid, animation: &Behavior<Animation>) -> AnimationId {
+        for_each_animation(animation, &mut |a| {
+            assert!(a.is_valid(), "only tweens can be played backward or yoyo");
+        });
+        let id = AnimationId(self.next_animation_id);
+        self.next_animation_id += 1;
+        self.animation_sprites.insert(id, sprite_id);
+        self.running.entry(sprite_id).or_default().push(RunningAnimation {
+            id,
+            behavior: animation.clone(),
+            state: State::new(animation.clone()),
+            paused: false,
+            elapsed: 0.0,
+            start: None,
+            done: None,
+        });
+        id
+    }
+
+    // The first animation of the sprite running `animation`
+    fn find(&self, sprite_id: Uuid, animation: &Behavior<Animation>) -> Option<AnimationId> {
+        self.running.get(&sprite_id)?.iter()
+            .find(|a| a.behavior == *animation)
+            .map(|a| a.id)
+    }
+
+    fn animation_mut(&mut self, id: AnimationId) -> Option<&mut RunningAnimation> {
+        let sprite_id = self.animation_sprites.get(&id)?;
+        self.running.get_mut(sprite_id)?.iter_mut().find(|a| a.id == id)
     }
 
     /// Pause a running animation of the sprite
+    ///
+    /// If the sprite runs the same behavior several times, the first one is
+    /// paused, use `pause_animation` to choose.
     pub fn pause(&mut self, sprite_id: Uuid, animation: &Behavior<Animation>) {
-        if let Some(index) = self.find(sprite_id, animation) {
-            let animations = self.running.get_mut(&sprite_id).unwrap();
-            let (b, s, _) = animations.remove(index);
-            animations.push((b, s, true));
+        if let Some(id) = self.find(sprite_id, animation) {
+            self.pause_animation(id);
         }
     }
 
     /// Resume a paused animation of the sprite
     pub fn resume(&mut self, sprite_id: Uuid, animation: &Behavior<Animation>) {
-        if let Some(index) = self.find(sprite_id, animation) {
-            let animations = self.running.get_mut(&sprite_id).unwrap();
-            let (b, s, _) = animations.remove(index);
-            animations.push((b, s, false));
+        if let Some(id) = self.find(sprite_id, animation) {
+            self.resume_animation(id);
         }
     }
 
     /// Toggle an animation of the sprite
     pub fn toggle(&mut self, sprite_id: Uuid, animation: &Behavior<Animation>) {
-        if let Some(index) = self.find(sprite_id, animation) {
-            let animations = self.running.get_mut(&sprite_id).unwrap();
-            let (b, s, paused) = animations.remove(index);
-            animations.push((b, s, !paused));
+        if let Some(id) = self.find(sprite_id, animation) {
+            self.toggle_animation(id);
         }
     }
 
     /// Stop a running animation of the sprite
     pub fn stop(&mut self, sprite_id: Uuid, animation: &Behavior<Animation>) {
-        if let Some(index) = self.find(sprite_id, animation) {
-            self.running.get_mut(&sprite_id).unwrap().remove(index);
+        if let Some(id) = self.find(sprite_id, animation) {
+            self.stop_animation(id);
+        }
+    }
+
+    /// Pause a running animation
+    pub fn pause_animation(&mut self, id: AnimationId) {
+        if let Some(animation) = self.animation_mut(id) {
+            animation.paused = true;
+        }
+    }
+
+    /// Resume a paused animation
+    pub fn resume_animation(&mut self, id: AnimationId) {
+        if let Some(animation) = self.animation_mut(id) {
+            animation.paused = false;
+        }
+    }
+
+    /// Pause a running animation, or resume a paused one
+    pub fn toggle_animation(&mut self, id: AnimationId) {
+        if let Some(animation) = self.animation_mut(id) {
+            animation.paused = !animation.paused;
+        }
+    }
+
+    /// Whether or not an animation is paused
+    pub fn is_paused(&self, id: AnimationId) -> bool {
+        self.animation(id).is_some_and(|a| a.paused)
+    }
+
+    /// Pause every running animation in the scene
+    pub fn pause_all(&mut self) {
+        for animation in self.running.values_mut().flatten() {
+            animation.paused = true;
+        }
+    }
+
+    /// Resume every paused animation in the scene
+    pub fn resume_all(&mut self) {
+        for animation in self.running.values_mut().flatten() {
+            animation.paused = false;
+        }
+    }
+
+    /// Pause the animations of the sprite and of all its children
+    pub fn pause_subtree(&mut self, sprite_id: Uuid) {
+        self.set_subtree_paused(sprite_id, true);
+    }
+
+    /// Resume the animations of the sprite and of all its children
+    pub fn resume_subtree(&mut self, sprite_id: Uuid) {
+        self.set_subtree_paused(sprite_id, false);
+    }
+
+    fn set_subtree_paused(&mut self, sprite_id: Uuid, paused: bool) {
+        let mut ids = Vec::new();
+        if let Some(sprite) = self.child(sprite_id) {
+            subtree_ids(sprite, &mut ids);
+        }
+        for id in ids {
+            if let Some(animations) = self.running.get_mut(&id) {
+                for animation in animations {
+                    animation.paused = paused;
+                }
+            }
+        }
+    }
+
+    /// Get the time a running animation ran for, in seconds
+    pub fn elapsed(&self, id: AnimationId) -> Option<f64> {
+        self.animation(id).map(|a| a.elapsed)
+    }
+
+    /// Get how far a running animation is, from 0 to 1
+    ///
+    /// Returns None if the animation is not running, or if its duration is
+    /// unknown because it waits for input, loops or depends on which
+    /// behavior succeeds.
+    pub fn progress(&self, id: AnimationId) -> Option<f64> {
+        let animation = self.animation(id)?;
+        let duration = behavior_duration(&animation.behavior)?;
+        Some(if duration > 0.0 { (animation.elapsed / duration).min(1.0) } else { 1.0 })
+    }
+
+    /// Jump a running animation to `t` seconds after it started
+    ///
+    /// The properties the animation changes are put back the way they were
+    /// before its first update, then the animation is replayed up to `t`.
+    /// Other animations and properties of the sprite are left alone. Camera
+    /// effects are not played again, but other effects which can not be
+    /// undone, like particle bursts, happen again. An animation which
+    /// reaches its end finishes on the next update, unless it is paused
+    /// or seeked again.
+    pub fn seek(&mut self, id: AnimationId, t: f64) {
+        let sprite_id = match self.animation_sprites.get(&id) {
+            Some(&sprite_id) => sprite_id,
+            None => return,
+        };
+        let animation = match self.running.get_mut(&sprite_id)
+            .and_then(|animations| animations.iter_mut().find(|a| a.id == id)) {
+            Some(animation) => animation,
+            None => return,
+        };
+        let sprite = match find_child_mut(&mut self.children, &self.children_index, sprite_id) {
+            Some(sprite) => sprite,
+            None => return,
+        };
+
+        match animation.start {
+            Some(ref start) => sprite.restore(start),
+            None => animation.start = Some(snapshot(sprite, &animation.behavior)),
         }
+        animation.state = State::new(animation.behavior.clone());
+        animation.elapsed = t.max(0.0);
+        let e: Event = UpdateArgs { dt: animation.elapsed }.into();
+        // the camera effects already happened, they are not played again
+        let status = step(&mut animation.state, sprite, &mut Camera::new(), &e);
+        animation.done = if status == Running { None } else { Some(status) };
+    }
+
+    fn animation(&self, id: AnimationId) -> Option<&RunningAnimation> {
+        let sprite_id = self.animation_sprites.get(&id)?;
+        self.running.get(sprite_id)?.iter().find(|a| a.id == id)
+    }
+
+    /// Stop an animation, it does not report an event
+    pub fn stop_animation(&mut self, id: AnimationId) {
+        if let Some(sprite_id) = self.animation_sprites.remove(&id) {
+            if let Some(animations) = self.running.get_mut(&sprite_id) {
+                animations.retain(|a| a.id != id);
+            }
+        }
+    }
+
+    /// Whether or not an animation is still running, paused animations are
+    pub fn is_running(&self, id: AnimationId) -> bool {
+        self.animation_sprites.contains_key(&id)
     }
 
     /// Stop all running animations of the sprite
     pub fn stop_all(&mut self, sprite_id: Uuid) {
-        self.running.remove(&sprite_id);
+        if let Some(animations) = self.running.remove(&sprite_id) {
+            for animation in animations {
+                self.animation_sprites.remove(&animation.id);
+            }
+        }
     }
 
     /// Get all the running animations in the scene
     pub fn running(&self) -> usize {
         let mut total = 0;
-        for (_, animations) in &self.running {
+        for animations in self.running.values() {
             total += animations.len();
         }
         total
@@ -204,26 +1043,69 @@ impl<I: ImageSize> Scene<I> {
         }
     }
 
-    /// Add sprite to scene
+    /// Add sprite to the default layer of the scene
     pub fn add_child(&mut self, sprite: Sprite<I>) -> Uuid {
+        self.add_child_to_layer(DEFAULT_LAYER, sprite)
+    }
+
+    /// Add sprite to a layer of the scene, the layer is created with order 0
+    /// if it does not exist
+    pub fn add_child_to_layer(&mut self, layer: &str, sprite: Sprite<I>) -> Uuid {
         let id = sprite.id();
         self.children.push(sprite);
-        self.children_index.insert(id.clone(), self.children.len() - 1);
+        self.children_index.insert(id, self.children.len() - 1);
+        if self.layer_index(layer).is_none() {
+            self.add_layer(layer, 0);
+        }
+        self.layer_mut(layer).unwrap().add_child(id);
         id
     }
 
-    fn stop_all_including_children(&mut self, sprite: &Sprite<I>) {
+    /// Move a top-level sprite to another layer, it is drawn on top of the
+    /// sprites already in that layer. Returns false if either the sprite or
+    /// the layer does not exist.
+    pub fn set_child_layer(&mut self, id: Uuid, layer: &str) -> bool {
+        let index = match self.layer_index(layer) {
+            Some(index) => index,
+            None => return false,
+        };
+        if !self.children_index.contains_key(&id) {
+            return false;
+        }
+
+        for layer in &mut self.layers {
+            layer.remove_child(id);
+        }
+        self.layers[index].add_child(id);
+        true
+    }
+
+    /// Get the name of the layer a top-level sprite belongs to
+    pub fn child_layer(&self, id: Uuid) -> Option<&str> {
+        self.layers.iter()
+            .find(|layer| layer.children().contains(&id))
+            .map(|layer| layer.get_name())
+    }
+
+    // Stop the animations and drop the handlers of a removed sprite
+    fn forget_including_children(&mut self, sprite: &Sprite<I>) {
         self.stop_all(sprite.id());
+        self.pointer_handlers.remove(&sprite.id());
         for child in sprite.children().iter() {
-            self.stop_all_including_children(child);
+            self.forget_including_children(child);
         }
     }
 
     /// Remove the child by `id` from the scene's children or grandchild
-    /// will stop all the animations run by this child
+    /// will stop all the animations run by this child and drop its pointer handlers
     pub fn remove_child(&mut self, id: Uuid) -> Option<Sprite<I>> {
         let removed = if let Some(index) = self.children_index.remove(&id) {
             let removed = self.children.remove(index);
+            for layer in &mut self.layers {
+                if layer.remove_child(id) {
+                    break;
+                }
+            }
             // Removing a element of vector will alter the index,
             // update the mapping from uuid to index.
             for i in index..self.children.len() {
@@ -232,17 +1114,12 @@ impl<I: ImageSize> Scene<I> {
             }
             Some(removed)
         } else {
-            for child in &mut self.children {
-                if let Some(c) = child.remove_child(id.clone()) {
-                    return Some(c);
-                }
-            }
-            None
+            self.children.iter_mut().filter_map(|child| child.remove_child(id)).next()
         };
 
-        if removed.is_some() {
+        if let Some(ref sprite) = removed {
             self.dead_sprites.remove(&id);
-            self.stop_all_including_children(removed.as_ref().unwrap());
+            self.forget_including_children(sprite);
         }
 
         removed
@@ -253,15 +1130,12 @@ impl<I: ImageSize> Scene<I> {
     /// animations, it is removed immediately. Children with paused animations
     /// will not be removed until the animations are resumed and completed.
     pub fn remove_child_when_done(&mut self, id: Uuid) {
-        match self.running_for_child(id) {
-            Some(n) => {
-                if n == 0 {
-                    self.remove_child(id);
-                } else {
-                    self.dead_sprites.insert(id);
-                }
-            },
-            None => {}
+        if let Some(n) = self.running_for_child(id) {
+            if n == 0 {
+                self.remove_child(id);
+            } else {
+                self.dead_sprites.insert(id);
+            }
         }
     }
 
@@ -271,7 +1145,7 @@ impl<I: ImageSize> Scene<I> {
             Some(&self.children[*index])
         } else {
             for child in &self.children {
-                if let Some(c) = child.child(id.clone()) {
+                if let Some(c) = child.child(id) {
                     return Some(c);
                 }
             }
@@ -281,16 +1155,7 @@ impl<I: ImageSize> Scene<I> {
 
     /// Find the child by `id` from this sprite's children or grandchild, mutability
     pub fn child_mut(&mut self, id: Uuid) -> Option<&mut Sprite<I>> {
-        if let Some(index) = self.children_index.get(&id) {
-            Some(&mut self.children[*index])
-        } else {
-            for child in &mut self.children {
-                if let Some(c) = child.child_mut(id.clone()) {
-                    return Some(c);
-                }
-            }
-            None
-        }
+        find_child_mut(&mut self.children, &self.children_index, id)
     }
 
     /// Get the list of top-level (non-child) sprites.
@@ -299,3 +1164,177 @@ impl<I: ImageSize> Scene<I> {
         &self.children
     }
 }
+
+// Returns the copy offsets needed to cover `viewport` and the remaining scroll
+fn wrap_offsets(scroll: Scalar, wrap: Option<Scalar>, viewport: Scalar) -> (Vec<Scalar>, Scalar) {
+    match wrap {
+        Some(size) if size > 0.0 => {
+            let copies = (viewport / size).ceil().max(1.0) as usize + 1;
+            ((0..copies).map(|i| i as Scalar * size).collect(), scroll.rem_euclid(size))
+        },
+        _ => (vec![0.0], scroll),
+    }
+}
+
+// Same as `Scene::child_mut`, but only borrows the fields it needs
+fn find_child_mut<'a, I: ImageSize>(
+    children: &'a mut [Sprite<I>],
+    children_index: &HashMap<Uuid, usize>,
+    id: Uuid
+) -> Option<&'a mut Sprite<I>> {
+    if let Some(index) = children_index.get(&id) {
+        Some(&mut children[*index])
+    } else {
+        for child in children.iter_mut() {
+            if let Some(c) = child.child_mut(id) {
+                return Some(c);
+            }
+        }
+        None
+    }
+}
+
+// The time scale of every sprite, multiplied by the time scales of its parents
+fn sprite_time_scales<I: ImageSize>(
+    sprites: &[Sprite<I>],
+    time_scale: f64,
+    out: &mut HashMap<Uuid, f64>
+) {
+    for sprite in sprites {
+        let time_scale = time_scale * sprite.get_time_scale();
+        out.insert(sprite.id(), time_scale);
+        sprite_time_scales(sprite.children(), time_scale, out);
+    }
+}
+
+// Whether or not any of `sprites` or their children has its own time scale
+fn has_time_scales<I: ImageSize>(sprites: &[Sprite<I>]) -> bool {
+    sprites.iter().any(|sprite| sprite.get_time_scale() != 1.0 || has_time_scales(sprite.children()))
+}
+
+// What `behavior` changes of the sprite
+fn snapshot<I: ImageSize>(sprite: &Sprite<I>, behavior: &Behavior<Animation>) -> SpriteSnapshot {
+    let mut properties = AnimatedProperties::default();
+    add_properties(behavior, &mut properties);
+    sprite.snapshot(properties)
+}
+
+// Mark the properties of a sprite the animations of `behavior` change
+fn add_properties(behavior: &Behavior<Animation>, p: &mut AnimatedProperties) {
+    for_each_animation(behavior, &mut |animation| animation.add_properties(p));
+}
+
+// Call `f` with every animation of `behavior`
+fn for_each_animation<F>(behavior: &Behavior<Animation>, f: &mut F)
+    where F: FnMut(&Animation)
+{
+    use ai_behavior::{
+        Action, After, AlwaysSucceed, Fail, If, Select, Sequence, WhenAll, WhenAny, While,
+    };
+
+    match *behavior {
+        Action(ref animation) => f(animation),
+        Fail(ref b) | AlwaysSucceed(ref b) => for_each_animation(b, f),
+        Select(ref behaviors) | Sequence(ref behaviors) |
+        WhenAll(ref behaviors) | WhenAny(ref behaviors) | After(ref behaviors) => {
+            for b in behaviors {
+                for_each_animation(b, f);
+            }
+        },
+        If(ref condition, ref success, ref failure) => {
+            for_each_animation(condition, f);
+            for_each_animation(success, f);
+            for_each_animation(failure, f);
+        },
+        While(ref condition, ref behaviors) => {
+            for_each_animation(condition, f);
+            for b in behaviors {
+                for_each_animation(b, f);
+            }
+        },
+        _ => {},
+    }
+}
+
+// The ids of the sprite and all its children
+fn subtree_ids<I: ImageSize>(sprite: &Sprite<I>, out: &mut Vec<Uuid>) {
+    out.push(sprite.id());
+    for child in sprite.children() {
+        subtree_ids(child, out);
+    }
+}
+
+// Send the event to the state of an animation of `sprite`
+fn step<I: ImageSize, E: GenericEvent>(
+    state: &mut State<Animation, AnimationState>,
+    sprite: &mut Sprite<I>,
+    camera: &mut Camera,
+    e: &E
+) -> Status {
+    let (status, _) = state.event(e, &mut |args| {
+        // camera effects belong to the scene, not the sprite,
+        // they happen at once so wrappers can not change them
+        match *camera_effect(args.action) {
+            Animation::Shake(trauma) => {
+                camera.add_trauma(trauma);
+                return (Success, args.dt);
+            },
+            Animation::ZoomPunch(dur, amount) => {
+                camera.zoom_punch(dur, amount);
+                return (Success, args.dt);
+            },
+            _ => {},
+        }
+
+        let (state, status, remain) = {
+            let start_state;
+            let state = match *args.state {
+                None => { start_state = args.action.to_state(sprite); &start_state },
+                Some(ref state) => state,
+            };
+            state.update(sprite, args.dt)
+        };
+        *args.state = state;
+        (status, remain)
+    });
+    status
+}
+
+// The camera effect under the ease, reverse and yoyo wrappers, if any
+fn camera_effect(animation: &Animation) -> &Animation {
+    match *animation {
+        Animation::Ease(_, ref inner) |
+        Animation::Reverse(ref inner) |
+        Animation::Yoyo(ref inner, _) => {
+            let effect = camera_effect(inner);
+            match *effect {
+                Animation::Shake(_) | Animation::ZoomPunch(..) => effect,
+                _ => animation,
+            }
+        },
+        _ => animation,
+    }
+}
+
+// How long the behavior runs for, if it does not depend on input or on
+// which child succeeds
+fn behavior_duration(behavior: &Behavior<Animation>) -> Option<f64> {
+    use ai_behavior::{ Action, After, AlwaysSucceed, Fail, Sequence, Wait, WhenAll, WhenAny };
+
+    let durations = |behaviors: &[Behavior<Animation>]| {
+        behaviors.iter().map(behavior_duration).collect::<Option<Vec<f64>>>()
+    };
+    match *behavior {
+        Wait(t) => Some(t),
+        Action(ref animation) => animation.duration(),
+        Fail(ref b) | AlwaysSucceed(ref b) => behavior_duration(b),
+        Sequence(ref behaviors) => durations(behaviors).map(|d| d.iter().sum()),
+        WhenAll(ref behaviors) | After(ref behaviors) => {
+            durations(behaviors).map(|d| d.iter().cloned().fold(0.0, f64::max))
+        },
+        WhenAny(ref behaviors) => {
+            durations(behaviors).and_then(|d| d.iter().cloned().reduce(f64::min))
+        },
+        _ => None,
+    }
+}
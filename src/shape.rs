@@ -0,0 +1,185 @@
+use graphics::{ self, DrawState, Graphics };
+use graphics::math::{ Scalar, Matrix2d, Vec2d };
+use graphics::types::{ Color, Rectangle };
+
+/// The outline of a shape
+#[derive(Clone, PartialEq, Debug)]
+pub enum Geometry {
+    /// width, height
+    Rectangle(Scalar, Scalar),
+    /// width, height, corner radius
+    RoundedRectangle(Scalar, Scalar, Scalar),
+    /// width, height
+    Ellipse(Scalar, Scalar),
+    /// A closed convex polygon through the points
+    Polygon(Vec<Vec2d>),
+    /// An open line through the points, it is always stroked,
+    /// 1 wide unless the style says otherwise
+    Polyline(Vec<Vec2d>),
+}
+
+/// How a shape is drawn
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ShapeStyle {
+    /// Fill the inside of the shape
+    Fill,
+    /// Draw the outline of the shape with the given line width
+    Stroke(Scalar),
+}
+
+/// An untextured shape, the content of a shape sprite
+///
+/// The shape is drawn with the sprite's color and opacity,
+/// so it can be animated like any other sprite.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Shape {
+    geometry: Geometry,
+    style: ShapeStyle,
+}
+
+impl Shape {
+    /// Create a shape
+    pub fn new(geometry: Geometry, style: ShapeStyle) -> Shape {
+        Shape {
+            geometry,
+            style,
+        }
+    }
+
+    /// Create a filled rectangle
+    pub fn rectangle(width: Scalar, height: Scalar) -> Shape {
+        Shape::new(Geometry::Rectangle(width, height), ShapeStyle::Fill)
+    }
+
+    /// Create a filled ellipse
+    pub fn ellipse(width: Scalar, height: Scalar) -> Shape {
+        Shape::new(Geometry::Ellipse(width, height), ShapeStyle::Fill)
+    }
+
+    /// Create a filled polygon
+    pub fn polygon(points: Vec<Vec2d>) -> Shape {
+        Shape::new(Geometry::Polygon(points), ShapeStyle::Fill)
+    }
+
+    /// Create a line through the points
+    pub fn polyline(points: Vec<Vec2d>, width: Scalar) -> Shape {
+        Shape::new(Geometry::Polyline(points), ShapeStyle::Stroke(width))
+    }
+
+    /// Get the shape's geometry
+    #[inline(always)]
+    pub fn get_geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    /// Set the shape's geometry
+    #[inline(always)]
+    pub fn set_geometry(&mut self, geometry: Geometry) {
+        self.geometry = geometry;
+    }
+
+    /// Get the shape's style
+    #[inline(always)]
+    pub fn get_style(&self) -> ShapeStyle {
+        self.style
+    }
+
+    /// Set the shape's style
+    #[inline(always)]
+    pub fn set_style(&mut self, style: ShapeStyle) {
+        self.style = style;
+    }
+
+    // The bounds of the points of a polygon or polyline, as [x, y, w, h]
+    fn point_bounds(points: &[Vec2d]) -> Rectangle {
+        if points.is_empty() {
+            return [0.0; 4];
+        }
+        let mut min = points[0];
+        let mut max = points[0];
+        for p in points {
+            min = [min[0].min(p[0]), min[1].min(p[1])];
+            max = [max[0].max(p[0]), max[1].max(p[1])];
+        }
+        [min[0], min[1], max[0] - min[0], max[1] - min[1]]
+    }
+
+    /// Get the size of the shape
+    ///
+    /// Polygons and polylines are as large as the bounds of their points.
+    pub fn size(&self) -> (Scalar, Scalar) {
+        match self.geometry {
+            Geometry::Rectangle(w, h) |
+            Geometry::RoundedRectangle(w, h, _) |
+            Geometry::Ellipse(w, h) => (w, h),
+            Geometry::Polygon(ref points) |
+            Geometry::Polyline(ref points) => {
+                let bounds = Shape::point_bounds(points);
+                (bounds[2], bounds[3])
+            },
+        }
+    }
+
+    /// Draw the shape in `rect`, as sized by `size`
+    pub fn draw<B: Graphics>(
+        &self,
+        rect: Rectangle,
+        color: Color,
+        draw_state: &DrawState,
+        t: Matrix2d,
+        b: &mut B
+    ) {
+        use graphics::{ Ellipse, Line, Polygon };
+
+        match (&self.geometry, self.style) {
+            (Geometry::Rectangle(..), ShapeStyle::Fill) => {
+                graphics::Rectangle::new(color).draw(rect, draw_state, t, b);
+            },
+            (Geometry::Rectangle(..), ShapeStyle::Stroke(width)) => {
+                graphics::Rectangle::new_border(color, width / 2.0).draw(rect, draw_state, t, b);
+            },
+            (Geometry::RoundedRectangle(_, _, radius), ShapeStyle::Fill) => {
+                graphics::Rectangle::new_round(color, *radius).draw(rect, draw_state, t, b);
+            },
+            (Geometry::RoundedRectangle(_, _, radius), ShapeStyle::Stroke(width)) => {
+                graphics::Rectangle::new_round_border(color, *radius, width / 2.0)
+                    .draw(rect, draw_state, t, b);
+            },
+            (Geometry::Ellipse(..), ShapeStyle::Fill) => {
+                Ellipse::new(color).draw(rect, draw_state, t, b);
+            },
+            (Geometry::Ellipse(..), ShapeStyle::Stroke(width)) => {
+                Ellipse::new_border(color, width / 2.0).draw(rect, draw_state, t, b);
+            },
+            (Geometry::Polygon(points), ShapeStyle::Fill) => {
+                let bounds = Shape::point_bounds(points);
+                let points: Vec<Vec2d> = points.iter()
+                    .map(|p| [p[0] - bounds[0] + rect[0], p[1] - bounds[1] + rect[1]])
+                    .collect();
+                Polygon::new(color).draw(&points, draw_state, t, b);
+            },
+            (Geometry::Polygon(points), ShapeStyle::Stroke(_)) |
+            (Geometry::Polyline(points), _) => {
+                let width = match self.style {
+                    ShapeStyle::Stroke(width) => width,
+                    ShapeStyle::Fill => 1.0,
+                };
+                let closed = match self.geometry {
+                    Geometry::Polygon(_) => points.len() > 2,
+                    _ => false,
+                };
+                let bounds = Shape::point_bounds(points);
+                let offset = [rect[0] - bounds[0], rect[1] - bounds[1]];
+                let line = Line::new_round(color, width / 2.0);
+                let segments = points.iter().zip(points.iter().skip(1));
+                let closing = if closed { points.last().zip(points.first()) } else { None };
+                for (p, q) in segments.chain(closing) {
+                    line.draw([
+                            p[0] + offset[0], p[1] + offset[1],
+                            q[0] + offset[0], q[1] + offset[1],
+                        ], draw_state, t, b);
+                }
+            },
+        }
+    }
+}
@@ -3,13 +3,78 @@ use std::collections::HashMap;
 
 use uuid::Uuid;
 
-use graphics::{ self, Graphics, ImageSize };
+use graphics::{ self, Graphics, ImageSize, Transformed };
+use graphics::character::CharacterCache;
 use graphics::math::{ Scalar, Matrix2d, Vec2d };
-use graphics::types::SourceRectangle;
+use graphics::types::{ Color, Rectangle, SourceRectangle };
+
+use batch::{ Quad, SpriteBatch };
+use clip::{ Clip, ClipStack };
+use collision::Collider;
+use pointer::DragAxis;
+use bmfont::BitmapText;
+use debug::DebugDraw;
+use particles::Emitter;
+use shape::Shape;
+use text::Label;
+
+/// What a sprite draws
+pub enum Content<I: ImageSize> {
+    /// A texture, or a rectangle selection of it
+    Texture(Rc<I>),
+    /// A text label
+    Text(Label<I>),
+    /// A text drawn from a bitmap font
+    BitmapText(BitmapText<I>),
+    /// An untextured shape
+    Shape(Shape),
+    /// A particle emitter
+    Emitter(Emitter<I>),
+}
+
+// The properties of a sprite an animation changes
+#[derive(Clone, Copy, Default)]
+pub(crate) struct AnimatedProperties {
+    pub visible: bool,
+    pub anchor: bool,
+    pub position: bool,
+    pub rotation: bool,
+    pub scale: bool,
+    pub color: bool,
+    pub velocity: bool,
+    pub angular_velocity: bool,
+    pub flip: bool,
+    pub opacity: bool,
+    pub clip: bool,
+    pub src_rect: bool,
+    pub visible_chars: bool,
+}
+
+// The properties of a sprite an animation changes, to play it again
+#[derive(Clone)]
+pub(crate) struct SpriteSnapshot {
+    properties: AnimatedProperties,
+    visible: bool,
+    anchor: Vec2d,
+    position: Vec2d,
+    rotation: Scalar,
+    scale: Vec2d,
+    color: [f32; 3],
+    velocity: Vec2d,
+    angular_velocity: Scalar,
+    flip_x: bool,
+    flip_y: bool,
+    opacity: f32,
+    clip: Option<Clip>,
+    src_rect: Option<SourceRectangle>,
+    visible_chars: Option<usize>,
+}
 
-/// A sprite is a texture with some properties.
+/// A sprite is a texture, or other content, with some properties.
 pub struct Sprite<I: ImageSize> {
     id: Uuid,
+    name: Option<String>,
+    debug: bool,
 
     visible: bool,
 
@@ -20,6 +85,12 @@ pub struct Sprite<I: ImageSize> {
     scale: Vec2d,
     color: [f32;3],
 
+    velocity: Vec2d,
+    acceleration: Vec2d,
+    angular_velocity: Scalar,
+    drag: Scalar,
+    time_scale: f64,
+
 
     flip_x: bool,
     flip_y: bool,
@@ -30,41 +101,54 @@ pub struct Sprite<I: ImageSize> {
     children_index: HashMap<Uuid, usize>,
 
     src_rect: Option<SourceRectangle>,
-    texture: Rc<I>,
+    content: Content<I>,
+    clip: Option<Clip>,
+    collider: Option<Collider>,
+
+    draggable: bool,
+    drag_axis: DragAxis,
+    drop_target: bool,
 }
 
 impl<I: ImageSize> Sprite<I> {
     /// Crate sprite from a texture
     pub fn from_texture(texture: Rc<I>) -> Sprite<I> {
-        Sprite {
-            id: Uuid::new_v4(),
-
-            visible: true,
-
-            anchor: [0.5, 0.5],
+        Sprite::from_content(Content::Texture(texture))
+    }
 
-            position: [0.0, 0.0],
-            rotation: 0.0,
-            scale: [1.0, 1.0],
-            color: [1.0,1.0,1.0],
+    /// Create sprite from a rectangle selection of a texture
+    pub fn from_texture_rect(texture: Rc<I>, src_rect: SourceRectangle) -> Sprite<I> {
+        let mut sprite = Sprite::from_texture(texture);
+        sprite.src_rect = Some(src_rect);
+        sprite
+    }
 
-            flip_x: false,
-            flip_y: false,
+    /// Create sprite from a text label
+    pub fn from_text(label: Label<I>) -> Sprite<I> {
+        Sprite::from_content(Content::Text(label))
+    }
 
-            opacity: 1.0,
+    /// Create sprite from a bitmap font text
+    pub fn from_bitmap_text(text: BitmapText<I>) -> Sprite<I> {
+        Sprite::from_content(Content::BitmapText(text))
+    }
 
-            texture: texture,
-            src_rect: None,
+    /// Create sprite from an untextured shape
+    pub fn from_shape(shape: Shape) -> Sprite<I> {
+        Sprite::from_content(Content::Shape(shape))
+    }
 
-            children: Vec::new(),
-            children_index: HashMap::new(),
-        }
+    /// Create sprite from a particle emitter
+    pub fn from_emitter(emitter: Emitter<I>) -> Sprite<I> {
+        Sprite::from_content(Content::Emitter(emitter))
     }
 
-    /// Create sprite from a rectangle selection of a texture
-    pub fn from_texture_rect(texture: Rc<I>, src_rect: SourceRectangle) -> Sprite<I> {
+    /// Create sprite from any content
+    pub fn from_content(content: Content<I>) -> Sprite<I> {
         Sprite {
             id: Uuid::new_v4(),
+            name: None,
+            debug: false,
 
             visible: true,
 
@@ -75,23 +159,59 @@ impl<I: ImageSize> Sprite<I> {
             scale: [1.0, 1.0],
             color: [1.0,1.0,1.0],
 
+            velocity: [0.0, 0.0],
+            acceleration: [0.0, 0.0],
+            angular_velocity: 0.0,
+            drag: 0.0,
+            time_scale: 1.0,
+
             flip_x: false,
             flip_y: false,
 
             opacity: 1.0,
 
-            texture: texture,
-            src_rect: From::from(src_rect),
+            content,
+            src_rect: None,
+            clip: None,
+            collider: None,
+
+            draggable: false,
+            drag_axis: DragAxis::Both,
+            drop_target: false,
 
             children: Vec::new(),
             children_index: HashMap::new(),
-        }        
+        }
     }
 
     /// Get the sprite's id
     #[inline(always)]
     pub fn id(&self) -> Uuid {
-        self.id.clone()
+        self.id
+    }
+
+    /// Get the sprite's name
+    #[inline(always)]
+    pub fn get_name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| &name[..])
+    }
+
+    /// Set the sprite's name, it is shown by the debug overlay
+    #[inline(always)]
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    /// Whether or not the debug overlay is drawn for this sprite
+    #[inline(always)]
+    pub fn get_debug(&self) -> bool {
+        self.debug
+    }
+
+    /// Set whether or not the debug overlay is drawn for this sprite
+    #[inline(always)]
+    pub fn set_debug(&mut self, debug: bool) {
+        self.debug = debug;
     }
 
     /// Whether or not the sprite is visible
@@ -142,6 +262,19 @@ impl<I: ImageSize> Sprite<I> {
         (self.color[0], self.color[1], self.color[2])
     }
 
+    /// Set the sprite's color and opacity at once
+    #[inline(always)]
+    pub fn set_rgba(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        self.color = [r, g, b];
+        self.opacity = a;
+    }
+
+    /// Get the sprite's color and opacity at once
+    #[inline(always)]
+    pub fn get_rgba(&self) -> (f32, f32, f32, f32) {
+        (self.color[0], self.color[1], self.color[2], self.opacity)
+    }
+
     /// Get the sprite's rotation (in degree)
     #[inline(always)]
     pub fn get_rotation(&self) -> Scalar {
@@ -166,6 +299,69 @@ impl<I: ImageSize> Sprite<I> {
         self.scale = [sx, sy];
     }
 
+    /// Get the sprite's velocity, in pixels per second
+    #[inline(always)]
+    pub fn get_velocity(&self) -> (Scalar, Scalar) {
+        (self.velocity[0], self.velocity[1])
+    }
+
+    /// Set the sprite's velocity, in pixels per second
+    #[inline(always)]
+    pub fn set_velocity(&mut self, vx: Scalar, vy: Scalar) {
+        self.velocity = [vx, vy];
+    }
+
+    /// Get the sprite's acceleration, in pixels per second squared
+    #[inline(always)]
+    pub fn get_acceleration(&self) -> (Scalar, Scalar) {
+        (self.acceleration[0], self.acceleration[1])
+    }
+
+    /// Set the sprite's acceleration, in pixels per second squared
+    #[inline(always)]
+    pub fn set_acceleration(&mut self, ax: Scalar, ay: Scalar) {
+        self.acceleration = [ax, ay];
+    }
+
+    /// Get the sprite's angular velocity (in degree per second)
+    #[inline(always)]
+    pub fn get_angular_velocity(&self) -> Scalar {
+        self.angular_velocity
+    }
+
+    /// Set the sprite's angular velocity (in degree per second)
+    #[inline(always)]
+    pub fn set_angular_velocity(&mut self, deg: Scalar) {
+        self.angular_velocity = deg;
+    }
+
+    /// Get the sprite's drag
+    #[inline(always)]
+    pub fn get_drag(&self) -> Scalar {
+        self.drag
+    }
+
+    /// Set the sprite's drag, the velocity is multiplied by `exp(-drag)` every second.
+    /// Default value is 0
+    #[inline(always)]
+    pub fn set_drag(&mut self, drag: Scalar) {
+        self.drag = drag;
+    }
+
+    /// Get the sprite's time scale
+    #[inline(always)]
+    pub fn get_time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Set the sprite's time scale, it multiplies the time of the sprite's
+    /// animations, motion and content, and of its children.
+    /// 0 pauses the subtree. Default value is 1
+    #[inline(always)]
+    pub fn set_time_scale(&mut self, time_scale: f64) {
+        self.time_scale = time_scale;
+    }
+
     /// Whether or not the sprite is flipped horizontally.
     ///
     /// It only flips the texture of the sprite,
@@ -231,22 +427,115 @@ impl<I: ImageSize> Sprite<I> {
     }
 
     /// Get the sprite's texture
+    ///
+    /// Panics if the sprite is not textured, use `texture` for sprites
+    /// which may have other content.
     #[inline(always)]
     pub fn get_texture(&self) -> &Rc<I> {
-        &self.texture
+        self.texture().expect("the sprite has no texture")
+    }
+
+    /// Get the sprite's texture, or the texture of its particles,
+    /// `None` for text and shape sprites
+    #[inline(always)]
+    pub fn texture(&self) -> Option<&Rc<I>> {
+        match self.content {
+            Content::Texture(ref texture) => Some(texture),
+            Content::Emitter(ref emitter) => Some(emitter.get_texture()),
+            _ => None,
+        }
     }
 
-    /// Set the sprite's texture
+    /// Set the sprite's texture, replacing its content
     #[inline(always)]
     pub fn set_texture(&mut self, texture: Rc<I>) {
-        self.texture = texture;
+        self.content = Content::Texture(texture);
+    }
+
+    /// Get the sprite's content
+    #[inline(always)]
+    pub fn get_content(&self) -> &Content<I> {
+        &self.content
+    }
+
+    /// Get the sprite's content, mutability
+    #[inline(always)]
+    pub fn get_content_mut(&mut self) -> &mut Content<I> {
+        &mut self.content
+    }
+
+    /// Set the sprite's content
+    #[inline(always)]
+    pub fn set_content(&mut self, content: Content<I>) {
+        self.content = content;
+    }
+
+    /// Get how the sprite clips its children
+    #[inline(always)]
+    pub fn get_clip(&self) -> Option<Clip> {
+        self.clip
+    }
+
+    /// Set how the sprite clips its children, `None` to not clip them
+    #[inline(always)]
+    pub fn set_clip(&mut self, clip: Option<Clip>) {
+        self.clip = clip;
+    }
+
+    /// Get the sprite's collider
+    #[inline(always)]
+    pub fn get_collider(&self) -> Option<&Collider> {
+        self.collider.as_ref()
+    }
+
+    /// Set the sprite's collider, `None` to not collide
+    #[inline(always)]
+    pub fn set_collider(&mut self, collider: Option<Collider>) {
+        self.collider = collider;
+    }
+
+    /// Whether or not the sprite follows the pointer when dragged
+    #[inline(always)]
+    pub fn get_draggable(&self) -> bool {
+        self.draggable
+    }
+
+    /// Set whether or not the sprite follows the pointer when dragged with
+    /// the left button
+    #[inline(always)]
+    pub fn set_draggable(&mut self, draggable: bool) {
+        self.draggable = draggable;
+    }
+
+    /// Get the directions the sprite can be dragged in
+    #[inline(always)]
+    pub fn get_drag_axis(&self) -> DragAxis {
+        self.drag_axis
+    }
+
+    /// Set the directions the sprite can be dragged in, in its parent's space
+    #[inline(always)]
+    pub fn set_drag_axis(&mut self, axis: DragAxis) {
+        self.drag_axis = axis;
+    }
+
+    /// Whether or not dragged sprites can be dropped on the sprite
+    #[inline(always)]
+    pub fn get_drop_target(&self) -> bool {
+        self.drop_target
+    }
+
+    /// Set whether or not dragged sprites can be dropped on the sprite
+    #[inline(always)]
+    pub fn set_drop_target(&mut self, drop_target: bool) {
+        self.drop_target = drop_target;
     }
 
     /// Add a sprite as the child of this sprite, return the added sprite's id.
     pub fn add_child(&mut self, sprite: Sprite<I>) -> Uuid {
         let id = sprite.id();
         self.children.push(sprite);
-        self.children_index.insert(id.clone(), self.children.len() - 1);
+        self.children_index.insert(id, self.children.len() - 1);
         id
     }
 
@@ -263,7 +552,7 @@ impl<I: ImageSize> Sprite<I> {
             Some(removed)
         } else {
             for child in &mut self.children {
-                if let Some(c) = child.remove_child(id.clone()) {
+                if let Some(c) = child.remove_child(id) {
                     return Some(c);
                 }
             }
@@ -277,7 +566,7 @@ impl<I: ImageSize> Sprite<I> {
             Some(&self.children[*index])
         } else {
             for child in &self.children {
-                if let Some(c) = child.child(id.clone()) {
+                if let Some(c) = child.child(id) {
                     return Some(c);
                 }
             }
@@ -291,7 +580,7 @@ impl<I: ImageSize> Sprite<I> {
             Some(&mut self.children[*index])
         } else {
             for child in &mut self.children {
-                if let Some(c) = child.child_mut(id.clone()) {
+                if let Some(c) = child.child_mut(id) {
                     return Some(c);
                 }
             }
@@ -305,116 +594,382 @@ impl<I: ImageSize> Sprite<I> {
         &self.children
     }
 
-    /// Draw this sprite and its children
-    pub fn draw<B: Graphics<Texture = I>>(&self, t: Matrix2d, b: &mut B) {
-        use graphics::*;
+    // The size of the content before scaling
+    pub(crate) fn content_size(&self) -> (Scalar, Scalar) {
+        match self.content {
+            Content::Texture(ref texture) => {
+                match self.src_rect {
+                    Some(src_rect) => (src_rect[2], src_rect[3]),
+                    None => {
+                        let (w, h) = texture.get_size();
+                        (w as Scalar, h as Scalar)
+                    }
+                }
+            },
+            Content::Text(ref label) => label.size(),
+            Content::BitmapText(ref text) => text.size(),
+            Content::Shape(ref shape) => shape.size(),
+            // particles are drawn around the emitter's position
+            Content::Emitter(_) => (0.0, 0.0),
+        }
+    }
 
-        if !self.visible {
-            return;
+    pub(crate) fn snapshot(&self, properties: AnimatedProperties) -> SpriteSnapshot {
+        SpriteSnapshot {
+            properties,
+            visible: self.visible,
+            anchor: self.anchor,
+            position: self.position,
+            rotation: self.rotation,
+            scale: self.scale,
+            color: self.color,
+            velocity: self.velocity,
+            angular_velocity: self.angular_velocity,
+            flip_x: self.flip_x,
+            flip_y: self.flip_y,
+            opacity: self.opacity,
+            clip: self.clip,
+            src_rect: self.src_rect,
+            visible_chars: match self.content {
+                Content::BitmapText(ref text) => text.get_visible_chars(),
+                _ => None,
+            },
         }
+    }
 
-        let (tex_w, tex_h) = self.texture.get_size();
-        let tex_w = tex_w as f64;
-        let tex_h = tex_h as f64;
-        let source_rectangle = self.src_rect.unwrap_or({
-            let (w, h) = (tex_w, tex_h);
-            [0.0, 0.0, w as f64, h as f64]
-        });        
-        let anchor = [self.anchor[0] * source_rectangle[2], self.anchor[1] * source_rectangle[3]];
+    // Put back the properties of the snapshot, other ones are left alone
+    pub(crate) fn restore(&mut self, snapshot: &SpriteSnapshot) {
+        let p = snapshot.properties;
+        if p.visible { self.visible = snapshot.visible; }
+        if p.anchor { self.anchor = snapshot.anchor; }
+        if p.position { self.position = snapshot.position; }
+        if p.rotation { self.rotation = snapshot.rotation; }
+        if p.scale { self.scale = snapshot.scale; }
+        if p.color { self.color = snapshot.color; }
+        if p.velocity { self.velocity = snapshot.velocity; }
+        if p.angular_velocity { self.angular_velocity = snapshot.angular_velocity; }
+        if p.flip {
+            self.flip_x = snapshot.flip_x;
+            self.flip_y = snapshot.flip_y;
+        }
+        if p.opacity { self.opacity = snapshot.opacity; }
+        if p.clip { self.clip = snapshot.clip; }
+        if p.src_rect { self.src_rect = snapshot.src_rect; }
+        if let (true, &mut Content::BitmapText(ref mut text)) = (p.visible_chars, &mut self.content) {
+            text.set_visible_chars(snapshot.visible_chars);
+        }
+    }
 
-        let transformed = t.trans(self.position[0], self.position[1])
-                           .rot_deg(self.rotation)
-                           .scale(self.scale[0], self.scale[1]);
+    /// Move this sprite and its children by their velocity and advance
+    /// the time of their content, the scene does this on every update
+    pub fn update(&mut self, dt: f64) {
+        let dt = dt * self.time_scale;
+        if self.velocity != [0.0, 0.0] || self.acceleration != [0.0, 0.0] {
+            let damping = (-self.drag * dt).exp();
+            self.velocity = [
+                (self.velocity[0] + self.acceleration[0] * dt) * damping,
+                (self.velocity[1] + self.acceleration[1] * dt) * damping,
+            ];
+            self.position = [
+                self.position[0] + self.velocity[0] * dt,
+                self.position[1] + self.velocity[1] * dt,
+            ];
+        }
+        self.rotation += self.angular_velocity * dt;
 
+        match self.content {
+            Content::BitmapText(ref mut text) => text.update(dt),
+            Content::Emitter(ref mut emitter) => emitter.update(dt),
+            _ => {},
+        }
+        for child in &mut self.children {
+            child.update(dt);
+        }
+    }
+
+    // The transform of the children in the space of `t`, without the content
+    pub(crate) fn transform(&self, t: Matrix2d) -> Matrix2d {
+        t.trans(self.position[0], self.position[1])
+         .rot_deg(self.rotation)
+         .scale(self.scale[0], self.scale[1])
+    }
+
+    // Returns the transform for the children, the transform of the content
+    // and the rectangle the content is drawn in
+    pub(crate) fn draw_params(&self, t: Matrix2d) -> (Matrix2d, Matrix2d, Rectangle) {
+        let (w, h) = self.content_size();
+        let anchor = [self.anchor[0] * w, self.anchor[1] * h];
+
+        let transformed = self.transform(t);
         let mut model = transformed;
 
         if self.flip_x {
-            model = model.trans(source_rectangle[2] - 2.0 * anchor[0], 0.0).flip_h();
+            model = model.trans(w - 2.0 * anchor[0], 0.0).flip_h();
         }
 
         if self.flip_y {
-            model = model.trans(0.0, source_rectangle[3] - 2.0 * anchor[1]).flip_v();
+            model = model.trans(0.0, h - 2.0 * anchor[1]).flip_v();
         }
 
-        let ref draw_state: graphics::DrawState = Default::default();
+        (transformed, model, [-anchor[0], -anchor[1], w, h])
+    }
+
+    fn draw_content<B: Graphics<Texture = I>>(
+        &self,
+        model: Matrix2d,
+        rect: Rectangle,
+        color: Color,
+        draw_state: &graphics::DrawState,
+        b: &mut B
+    ) {
+        match self.content {
+            Content::Texture(ref texture) => {
+                graphics::Image::new()
+                    .color(color)
+                    .rect(rect)
+                    .maybe_src_rect(self.src_rect)
+                    .draw(&**texture, draw_state, model, b);
+            },
+            Content::Text(ref label) => {
+                label.draw(rect, color, draw_state, model, b);
+            },
+            Content::BitmapText(ref text) => {
+                text.for_each_glyph(rect, color, |texture, rect, src_rect, color| {
+                    graphics::Image::new_color(color)
+                        .rect(rect)
+                        .src_rect(src_rect)
+                        .draw(texture, draw_state, model, b);
+                });
+            },
+            Content::Shape(ref shape) => {
+                shape.draw(rect, color, draw_state, model, b);
+            },
+            Content::Emitter(ref emitter) => {
+                let texture = &**emitter.get_texture();
+                emitter.for_each_particle(self.src_rect, color, |rect, color| {
+                    graphics::Image::new_color(color)
+                        .rect(rect)
+                        .maybe_src_rect(self.src_rect)
+                        .draw(texture, draw_state, model, b);
+                });
+            },
+        }
+    }
 
-        // for debug: bounding_box
-        //model.rgb(1.0, 0.0, 0.0).draw(b);
+    // Draw the content to the stencil buffer, for `Clip::Content`
+    pub(crate) fn draw_mask<B: Graphics<Texture = I>>(
+        &self,
+        model: Matrix2d,
+        rect: Rectangle,
+        draw_state: &graphics::DrawState,
+        b: &mut B
+    ) {
+        self.draw_content(model, rect, [1.0; 4], draw_state, b);
+    }
 
-        graphics::Image::new()
-            .color([self.color[0], self.color[1], self.color[2], self.opacity])
-            .rect([-anchor[0], -anchor[1], source_rectangle[2], source_rectangle[3]])
-            .maybe_src_rect(self.src_rect)
-            .draw(&*self.texture, draw_state, model, b);
+    /// Draw this sprite and its children
+    pub fn draw<B: Graphics<Texture = I>>(&self, t: Matrix2d, b: &mut B) {
+        self.draw_clipped(t, None, &mut ClipStack::new(), b);
+    }
 
-        // for debug: anchor point
-        //c.trans(self.position[0], self.position[1]).rect(-5.0, -5.0, 10.0, 10.0).rgb(0.0, 0.0, 1.0).draw(b);
+    /// Draw this sprite and its children with color
+    pub fn draw_tinted<B: Graphics<Texture = I>>(&self, t: Matrix2d, b: &mut B, c: [f32;3]) {
+        self.draw_clipped(t, Some(c), &mut ClipStack::new(), b);
+    }
+
+    fn draw_clipped<'a, B>(
+        &'a self,
+        t: Matrix2d,
+        tint: Option<[f32; 3]>,
+        clips: &mut ClipStack<'a, I>,
+        b: &mut B
+    )
+        where B: Graphics<Texture = I>
+    {
+        if !self.visible {
+            return;
+        }
+
+        let (transformed, model, rect) = self.draw_params(t);
+        let c = tint.unwrap_or(self.color);
+        if self.clip != Some(Clip::Content) {
+            let draw_state = &clips.draw_state();
+            self.draw_content(model, rect, [c[0], c[1], c[2], self.opacity], draw_state, b);
+        }
 
+        if self.children.is_empty() {
+            return;
+        }
+        let saved = self.clip.map(|clip| {
+            (clip, clips.push(self, clip, transformed, model, rect, b))
+        });
         for child in &self.children {
-            child.draw(transformed, b);
+            child.draw_clipped(transformed, tint, clips, b);
+        }
+        if let Some((clip, saved)) = saved {
+            clips.pop(clip, saved, b);
         }
     }
 
-    /// Draw this sprite and its children with color
-    pub fn draw_tinted<B: Graphics<Texture = I>>(&self, t: Matrix2d, b: &mut B, c: [f32;3]) {
-        use graphics::*;
+    /// Draw the debug overlay of this sprite and its children
+    ///
+    /// `m` is the transform from the parent's space to the space of `t`,
+    /// so lines keep the same width whatever the sprite's scale.
+    pub fn draw_debug<B: Graphics<Texture = I>>(
+        &self,
+        t: Matrix2d,
+        m: Matrix2d,
+        options: &DebugDraw,
+        b: &mut B
+    ) {
+        use graphics::math::transform_pos;
 
         if !self.visible {
             return;
         }
 
-        let (tex_w, tex_h) = self.texture.get_size();
-        let tex_w = tex_w as f64;
-        let tex_h = tex_h as f64;
-        let source_rectangle = self.src_rect.unwrap_or({
-            let (w, h) = (tex_w, tex_h);
-            [0.0, 0.0, w as f64, h as f64]
-        });        
-        let anchor = [self.anchor[0] * source_rectangle[2], self.anchor[1] * source_rectangle[3]];
+        let (transformed, model, rect) = self.draw_params(m);
+        let draw_state = &graphics::DrawState::default();
+        let anchor = transform_pos(transformed, [0.0, 0.0]);
+        let selected = options.all || self.debug;
+
+        if selected && options.bounds {
+            let line = graphics::Line::new(options.bounds_color, options.line_radius);
+            let corners = [
+                transform_pos(model, [rect[0], rect[1]]),
+                transform_pos(model, [rect[0] + rect[2], rect[1]]),
+                transform_pos(model, [rect[0] + rect[2], rect[1] + rect[3]]),
+                transform_pos(model, [rect[0], rect[1] + rect[3]]),
+            ];
+            for i in 0..4 {
+                let (p, q) = (corners[i], corners[(i + 1) % 4]);
+                line.draw([p[0], p[1], q[0], q[1]], draw_state, t, b);
+            }
+        }
 
-        let transformed = t.trans(self.position[0], self.position[1])
-                           .rot_deg(self.rotation)
-                           .scale(self.scale[0], self.scale[1]);
+        for child in &self.children {
+            if selected && options.links && child.visible {
+                let p = transform_pos(transformed, child.position);
+                graphics::Line::new(options.link_color, options.line_radius)
+                    .draw([anchor[0], anchor[1], p[0], p[1]], draw_state, t, b);
+            }
+            child.draw_debug(t, transformed, options, b);
+        }
 
-        let mut model = transformed;
+        if selected && options.anchors {
+            let r = options.anchor_radius;
+            graphics::Rectangle::new(options.anchor_color)
+                .draw([anchor[0] - r, anchor[1] - r, 2.0 * r, 2.0 * r], draw_state, t, b);
+        }
+    }
 
-        if self.flip_x {
-            model = model.trans(source_rectangle[2] - 2.0 * anchor[0], 0.0).flip_h();
+    /// Draw the names of this sprite and its children, next to their bounds
+    pub fn draw_debug_names<C, B>(
+        &self,
+        t: Matrix2d,
+        m: Matrix2d,
+        options: &DebugDraw,
+        glyphs: &mut C,
+        b: &mut B
+    ) -> Result<(), C::Error>
+        where C: CharacterCache<Texture = I>,
+              B: Graphics<Texture = I>
+    {
+        use graphics::math::transform_pos;
+
+        if !self.visible {
+            return Ok(());
         }
 
-        if self.flip_y {
-            model = model.trans(0.0, source_rectangle[3] - 2.0 * anchor[1]).flip_v();
+        let (transformed, model, rect) = self.draw_params(m);
+        if let Some(ref name) = self.name {
+            if options.all || self.debug {
+                let p = transform_pos(model, [rect[0], rect[1]]);
+                graphics::Text::new_color(options.name_color, options.font_size)
+                    .draw(name, glyphs, &graphics::DrawState::default(), t.trans(p[0], p[1] - 2.0), b)?;
+            }
         }
-        
-        let ref draw_state: graphics::DrawState = Default::default();
 
-        // for debug: bounding_box
-        //model.rgb(1.0, 0.0, 0.0).draw(b);
+        for child in &self.children {
+            child.draw_debug_names(t, transformed, options, glyphs, b)?;
+        }
+        Ok(())
+    }
+
+    /// Draw this sprite and its children through `batch`
+    ///
+    /// Content that can not be batched draws the batch first,
+    /// then is drawn directly. So are the subtrees of clipping sprites.
+    pub fn draw_batched<'a, B>(&'a self, t: Matrix2d, batch: &mut SpriteBatch<'a, I>, b: &mut B)
+        where B: Graphics<Texture = I>
+    {
+        if !self.visible {
+            return;
+        }
 
-        graphics::Image::new()
-            .color([c[0], c[1], c[2], self.opacity])
-            .rect([-anchor[0], -anchor[1], source_rectangle[2], source_rectangle[3]])
-            .maybe_src_rect(self.src_rect)
-            .draw(&*self.texture, draw_state, model, b);
+        if self.clip.is_some() {
+            batch.draw(b);
+            batch.clear();
+            self.draw(t, b);
+            return;
+        }
 
-        // for debug: anchor point
-        //c.trans(self.position[0], self.position[1]).rect(-5.0, -5.0, 10.0, 10.0).rgb(0.0, 0.0, 1.0).draw(b);
+        let (transformed, model, rect) = self.draw_params(t);
+        let color = [self.color[0], self.color[1], self.color[2], self.opacity];
+
+        match self.content {
+            Content::Texture(ref texture) => {
+                let (w, h) = texture.get_size();
+                batch.push(Quad {
+                    texture: &**texture,
+                    transform: model,
+                    rect,
+                    src_rect: self.src_rect.unwrap_or([0.0, 0.0, w as f64, h as f64]),
+                    color,
+                });
+            },
+            Content::BitmapText(ref text) => {
+                text.for_each_glyph(rect, color, |texture, rect, src_rect, color| {
+                    batch.push(Quad {
+                        texture,
+                        transform: model,
+                        rect,
+                        src_rect,
+                        color,
+                    });
+                });
+            },
+            Content::Emitter(ref emitter) => {
+                let texture = &**emitter.get_texture();
+                let (w, h) = texture.get_size();
+                let src_rect = self.src_rect.unwrap_or([0.0, 0.0, w as f64, h as f64]);
+                emitter.for_each_particle(self.src_rect, color, |rect, color| {
+                    batch.push(Quad {
+                        texture,
+                        transform: model,
+                        rect,
+                        src_rect,
+                        color,
+                    });
+                });
+            },
+            _ => {
+                batch.draw(b);
+                batch.clear();
+                self.draw_content(model, rect, color, &graphics::DrawState::default(), b);
+            },
+        }
 
         for child in &self.children {
-            child.draw_tinted(transformed, b, c);
+            child.draw_batched(transformed, batch, b);
         }
     }
 
-
     /// Get the sprite's bounding box
     pub fn bounding_box(&self) -> graphics::types::Rectangle {
-        let (w, h) = self.texture.get_size();
-        let source_rectangle = self.src_rect.unwrap_or({
-            let (sprite_w, sprite_h) = (w, h);
-            [0.0, 0.0, sprite_w as f64, sprite_h as f64]
-        });                
-        let sprite_w = source_rectangle[2] * self.scale[0];
-        let sprite_h = source_rectangle[3] * self.scale[1];
+        let (w, h) = self.content_size();
+        let sprite_w = w * self.scale[0];
+        let sprite_h = h * self.scale[1];
 
         [
             self.position[0] - self.anchor[0] * sprite_w,
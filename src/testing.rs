@@ -0,0 +1,263 @@
+//! A headless graphics back-end that records what is drawn,
+//! so tests can assert on the output of `Scene::draw` without a GPU.
+//!
+//! Enabled by the `testing` feature.
+
+use graphics::{ DrawState, Graphics, ImageSize };
+use graphics::types::Color;
+
+/// A texture which only has an id and a size
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestTexture {
+    id: usize,
+    width: u32,
+    height: u32,
+}
+
+impl TestTexture {
+    /// Create a texture, `id` is what recorded draw calls refer to
+    pub fn new(id: usize, width: u32, height: u32) -> TestTexture {
+        TestTexture {
+            id,
+            width,
+            height,
+        }
+    }
+
+    /// Get the texture's id
+    #[inline(always)]
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+impl ImageSize for TestTexture {
+    fn get_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+/// One recorded call to the back-end
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawCall {
+    /// The id of the texture, `None` for untextured triangles
+    pub texture: Option<usize>,
+    /// The draw state of the call
+    pub draw_state: DrawState,
+    /// The vertex positions, three per triangle
+    pub vertices: Vec<[f32; 2]>,
+    /// The texture coordinates, empty for untextured triangles
+    pub uvs: Vec<[f32; 2]>,
+    /// The color of every vertex
+    pub colors: Vec<Color>,
+}
+
+/// Six consecutive textured vertices, which is how images are drawn
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RecordedQuad {
+    /// The id of the texture
+    pub texture: usize,
+    /// The vertex positions of the two triangles
+    pub vertices: [[f32; 2]; 6],
+    /// The texture coordinates of the two triangles
+    pub uvs: [[f32; 2]; 6],
+    /// The color of the first vertex
+    pub color: Color,
+}
+
+impl RecordedQuad {
+    /// Get the axis aligned bounds of the quad, as [x, y, w, h]
+    pub fn bounds(&self) -> [f32; 4] {
+        let mut min = self.vertices[0];
+        let mut max = self.vertices[0];
+        for v in self.vertices.iter() {
+            min = [min[0].min(v[0]), min[1].min(v[1])];
+            max = [max[0].max(v[0]), max[1].max(v[1])];
+        }
+        [min[0], min[1], max[0] - min[0], max[1] - min[1]]
+    }
+
+    /// Get the center of the quad
+    pub fn center(&self) -> [f32; 2] {
+        let b = self.bounds();
+        [b[0] + b[2] / 2.0, b[1] + b[3] / 2.0]
+    }
+}
+
+/// A graphics back-end which records every call instead of drawing
+#[derive(Default)]
+pub struct RecordingGraphics {
+    calls: Vec<DrawCall>,
+    clear_color: Option<Color>,
+    stencil_clears: usize,
+}
+
+impl RecordingGraphics {
+    /// Create a back-end with nothing recorded
+    pub fn new() -> RecordingGraphics {
+        RecordingGraphics::default()
+    }
+
+    /// Get the recorded draw calls, in order
+    #[inline(always)]
+    pub fn calls(&self) -> &[DrawCall] {
+        &self.calls
+    }
+
+    /// Get the last color the screen was cleared with
+    #[inline(always)]
+    pub fn clear_color(&self) -> Option<Color> {
+        self.clear_color
+    }
+
+    /// Get how many times the stencil buffer was cleared
+    #[inline(always)]
+    pub fn stencil_clears(&self) -> usize {
+        self.stencil_clears
+    }
+
+    /// Get every textured quad, in draw order
+    pub fn quads(&self) -> Vec<RecordedQuad> {
+        let mut quads = Vec::new();
+        for call in &self.calls {
+            let texture = match call.texture {
+                Some(texture) => texture,
+                None => continue,
+            };
+            for i in 0..call.vertices.len() / 6 {
+                let mut quad = RecordedQuad {
+                    texture,
+                    vertices: [[0.0; 2]; 6],
+                    uvs: [[0.0; 2]; 6],
+                    color: call.colors[i * 6],
+                };
+                quad.vertices.copy_from_slice(&call.vertices[i * 6..i * 6 + 6]);
+                quad.uvs.copy_from_slice(&call.uvs[i * 6..i * 6 + 6]);
+                quads.push(quad);
+            }
+        }
+        quads
+    }
+
+    /// Get the texture ids of the quads, in draw order
+    pub fn textures(&self) -> Vec<usize> {
+        self.quads().iter().map(|q| q.texture).collect()
+    }
+
+    /// Get the number of untextured triangles
+    pub fn untextured_triangles(&self) -> usize {
+        self.calls.iter()
+            .filter(|call| call.texture.is_none())
+            .map(|call| call.vertices.len() / 3)
+            .sum()
+    }
+
+    /// Forget everything recorded so far
+    pub fn clear(&mut self) {
+        self.calls.clear();
+        self.clear_color = None;
+        self.stencil_clears = 0;
+    }
+
+    /// Panic unless exactly `n` quads were drawn
+    pub fn assert_quad_count(&self, n: usize) {
+        let count = self.quads().len();
+        assert!(count == n, "expected {} quads, {} were drawn", n, count);
+    }
+
+    /// Panic unless the quads were drawn with these textures, in this order
+    pub fn assert_textures(&self, ids: &[usize]) {
+        let textures = self.textures();
+        assert!(textures == ids, "expected textures {:?}, got {:?}", ids, textures);
+    }
+
+    /// Panic unless quad `index` covers `bounds` ([x, y, w, h]) within `epsilon`
+    pub fn assert_quad_bounds(&self, index: usize, bounds: [f32; 4], epsilon: f32) {
+        let quads = self.quads();
+        let quad = quads.get(index)
+            .unwrap_or_else(|| panic!("quad {} was not drawn, only {}", index, quads.len()));
+        let actual = quad.bounds();
+        let close = actual.iter().zip(bounds.iter()).all(|(a, b)| (a - b).abs() <= epsilon);
+        assert!(close, "expected quad {} at {:?}, got {:?}", index, bounds, actual);
+    }
+
+    /// Panic unless quad `index` has `color` within `epsilon`
+    pub fn assert_quad_color(&self, index: usize, color: Color, epsilon: f32) {
+        let quads = self.quads();
+        let quad = quads.get(index)
+            .unwrap_or_else(|| panic!("quad {} was not drawn, only {}", index, quads.len()));
+        let close = quad.color.iter().zip(color.iter()).all(|(a, b)| (a - b).abs() <= epsilon);
+        assert!(close, "expected quad {} with color {:?}, got {:?}", index, color, quad.color);
+    }
+
+    fn record(&mut self, draw_state: &DrawState, texture: Option<usize>) -> &mut DrawCall {
+        self.calls.push(DrawCall {
+            texture,
+            draw_state: *draw_state,
+            vertices: Vec::new(),
+            uvs: Vec::new(),
+            colors: Vec::new(),
+        });
+        self.calls.last_mut().unwrap()
+    }
+}
+
+impl Graphics for RecordingGraphics {
+    type Texture = TestTexture;
+
+    fn clear_color(&mut self, color: Color) {
+        self.clear_color = Some(color);
+    }
+
+    fn clear_stencil(&mut self, _value: u8) {
+        self.stencil_clears += 1;
+    }
+
+    fn tri_list<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], mut f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]]))
+    {
+        let call = self.record(draw_state, None);
+        f(&mut |vertices| {
+            call.vertices.extend_from_slice(vertices);
+            call.colors.extend(vertices.iter().map(|_| *color));
+        });
+    }
+
+    fn tri_list_c<F>(&mut self, draw_state: &DrawState, mut f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]], &[[f32; 4]]))
+    {
+        let call = self.record(draw_state, None);
+        f(&mut |vertices, colors| {
+            call.vertices.extend_from_slice(vertices);
+            call.colors.extend_from_slice(colors);
+        });
+    }
+
+    fn tri_list_uv<F>(
+        &mut self,
+        draw_state: &DrawState,
+        color: &[f32; 4],
+        texture: &TestTexture,
+        mut f: F
+    )
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]], &[[f32; 2]]))
+    {
+        let call = self.record(draw_state, Some(texture.id()));
+        f(&mut |vertices, uvs| {
+            call.vertices.extend_from_slice(vertices);
+            call.uvs.extend_from_slice(uvs);
+            call.colors.extend(vertices.iter().map(|_| *color));
+        });
+    }
+
+    fn tri_list_uv_c<F>(&mut self, draw_state: &DrawState, texture: &TestTexture, mut f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]], &[[f32; 2]], &[[f32; 4]]))
+    {
+        let call = self.record(draw_state, Some(texture.id()));
+        f(&mut |vertices, uvs, colors| {
+            call.vertices.extend_from_slice(vertices);
+            call.uvs.extend_from_slice(uvs);
+            call.colors.extend_from_slice(colors);
+        });
+    }
+}
@@ -0,0 +1,260 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use graphics::{ self, DrawState, Graphics, ImageSize, Transformed };
+use graphics::character::{ Character, CharacterCache };
+use graphics::math::{ Scalar, Matrix2d };
+use graphics::types::{ Color, FontSize, Rectangle };
+
+/// A source of rendered characters, implemented for every `CharacterCache`
+///
+/// Labels keep their character cache behind this trait so that a scene can
+/// draw them without knowing the type of the cache.
+pub trait Glyphs<I: ImageSize> {
+    /// Get a rendered character, `None` if it could not be rendered
+    fn character(&mut self, font_size: FontSize, ch: char) -> Option<Character<'_, I>>;
+}
+
+impl<C: CharacterCache> Glyphs<C::Texture> for C {
+    fn character(&mut self, font_size: FontSize, ch: char) -> Option<Character<'_, C::Texture>> {
+        CharacterCache::character(self, font_size, ch).ok()
+    }
+}
+
+/// Horizontal alignment of the lines of a label
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TextAlign {
+    /// Lines start at the left edge
+    Left,
+    /// Lines are centered
+    Center,
+    /// Lines end at the right edge
+    Right,
+}
+
+// The lines of a text with their width, and the size of the text
+pub(crate) struct Layout {
+    pub lines: Vec<(String, Scalar)>,
+    pub size: (Scalar, Scalar),
+}
+
+impl Layout {
+    pub fn new(lines: Vec<(String, Scalar)>, line_height: Scalar) -> Layout {
+        let w = lines.iter().map(|&(_, w)| w).fold(0.0, Scalar::max);
+        let h = lines.len() as Scalar * line_height;
+        Layout { lines, size: (w, h) }
+    }
+}
+
+/// A text label, the content of a text sprite
+///
+/// The text is drawn with the sprite's color and opacity,
+/// and the sprite's anchor is relative to the size of the laid out text.
+///
+/// The character cache is borrowed when the label is drawn, and when it is
+/// laid out again after its text, font size, wrap width or line spacing
+/// changed.
+pub struct Label<I: ImageSize> {
+    text: String,
+    glyphs: Rc<RefCell<dyn Glyphs<I>>>,
+    font_size: FontSize,
+    align: TextAlign,
+    wrap_width: Option<Scalar>,
+    line_spacing: Scalar,
+    layout: RefCell<Option<Rc<Layout>>>,
+}
+
+impl<I: ImageSize> Label<I> {
+    /// Create a label drawn with a character cache
+    pub fn new<G>(glyphs: Rc<RefCell<G>>, text: &str, font_size: FontSize) -> Label<I>
+        where G: Glyphs<I> + 'static
+    {
+        Label {
+            text: text.to_owned(),
+            glyphs,
+            font_size,
+            align: TextAlign::Left,
+            wrap_width: None,
+            line_spacing: 1.2,
+            layout: RefCell::new(None),
+        }
+    }
+
+    /// Get the label's text
+    #[inline(always)]
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Set the label's text
+    #[inline(always)]
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_owned();
+        self.layout = RefCell::new(None);
+    }
+
+    /// Get the label's font size
+    #[inline(always)]
+    pub fn get_font_size(&self) -> FontSize {
+        self.font_size
+    }
+
+    /// Set the label's font size
+    #[inline(always)]
+    pub fn set_font_size(&mut self, font_size: FontSize) {
+        self.font_size = font_size;
+        self.layout = RefCell::new(None);
+    }
+
+    /// Get the label's alignment
+    #[inline(always)]
+    pub fn get_align(&self) -> TextAlign {
+        self.align
+    }
+
+    /// Set the label's alignment
+    #[inline(always)]
+    pub fn set_align(&mut self, align: TextAlign) {
+        self.align = align;
+    }
+
+    /// Get the width lines are wrapped at
+    #[inline(always)]
+    pub fn get_wrap_width(&self) -> Option<Scalar> {
+        self.wrap_width
+    }
+
+    /// Set the width lines are wrapped at, `None` to only break lines at '\n'
+    #[inline(always)]
+    pub fn set_wrap_width(&mut self, wrap_width: Option<Scalar>) {
+        self.wrap_width = wrap_width;
+        self.layout = RefCell::new(None);
+    }
+
+    /// Get the distance between lines, relative to the font size
+    #[inline(always)]
+    pub fn get_line_spacing(&self) -> Scalar {
+        self.line_spacing
+    }
+
+    /// Set the distance between lines, relative to the font size.
+    /// Default value is 1.2
+    #[inline(always)]
+    pub fn set_line_spacing(&mut self, line_spacing: Scalar) {
+        self.line_spacing = line_spacing;
+        self.layout = RefCell::new(None);
+    }
+
+    /// Get the size of the laid out text
+    pub fn size(&self) -> (Scalar, Scalar) {
+        self.layout().size
+    }
+
+    fn line_height(&self) -> Scalar {
+        self.font_size as Scalar * self.line_spacing
+    }
+
+    // The lines of text with their width, laid out again after a change
+    fn layout(&self) -> Rc<Layout> {
+        if let Some(ref layout) = *self.layout.borrow() {
+            return layout.clone();
+        }
+
+        let lines = {
+            let mut glyphs = self.glyphs.borrow_mut();
+            let font_size = self.font_size;
+            layout_lines(&self.text, self.wrap_width, |_, s| {
+                s.chars()
+                    .filter_map(|ch| glyphs.character(font_size, ch).map(|c| c.advance_width()))
+                    .sum()
+            })
+        };
+        let layout = Rc::new(Layout::new(lines, self.line_height()));
+        *self.layout.borrow_mut() = Some(layout.clone());
+        layout
+    }
+
+    /// Draw the label in `rect`, as laid out by `size`
+    pub fn draw<B: Graphics<Texture = I>>(
+        &self,
+        rect: Rectangle,
+        color: Color,
+        draw_state: &DrawState,
+        t: Matrix2d,
+        b: &mut B
+    ) {
+        let layout = self.layout();
+        let line_height = self.line_height();
+        let mut glyphs = self.glyphs.borrow_mut();
+        let image = graphics::Image::new_color(color);
+
+        for (i, &(ref line, line_width)) in layout.lines.iter().enumerate() {
+            let mut x = rect[0] + align_offset(self.align, rect[2], line_width);
+            let y = rect[1] + i as Scalar * line_height + self.font_size as Scalar;
+
+            for ch in line.chars() {
+                if let Some(character) = glyphs.character(self.font_size, ch) {
+                    image.src_rect([
+                            character.atlas_offset[0],
+                            character.atlas_offset[1],
+                            character.atlas_size[0],
+                            character.atlas_size[1],
+                        ])
+                        .draw(character.texture, draw_state,
+                              t.trans(x + character.left(), y - character.top()), b);
+                    x += character.advance_width();
+                }
+            }
+        }
+    }
+}
+
+// Break `text` at '\n' and, if `wrap` is set, between words so lines fit in it.
+// `width` measures a string following a character, or the start of a line.
+// Returns the lines with their width.
+pub(crate) fn layout_lines<F>(text: &str, wrap: Option<Scalar>, mut width: F) -> Vec<(String, Scalar)>
+    where F: FnMut(Option<char>, &str) -> Scalar
+{
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let wrap = match wrap {
+            Some(wrap) => wrap,
+            None => {
+                lines.push((paragraph.to_owned(), width(None, paragraph)));
+                continue;
+            }
+        };
+
+        // only the added word is measured, the line keeps its width
+        let mut line = String::new();
+        let mut line_width = 0.0;
+        for word in paragraph.split(' ') {
+            if line.is_empty() {
+                line.push_str(word);
+                line_width = width(None, word);
+                continue;
+            }
+            let added = width(line.chars().last(), &format!(" {}", word));
+            if line_width + added > wrap {
+                lines.push((line, line_width));
+                line = word.to_owned();
+                line_width = width(None, word);
+            } else {
+                line.push(' ');
+                line.push_str(word);
+                line_width += added;
+            }
+        }
+        lines.push((line, line_width));
+    }
+    lines
+}
+
+// Horizontal offset of a line within the width of the text
+pub(crate) fn align_offset(align: TextAlign, width: Scalar, line_width: Scalar) -> Scalar {
+    match align {
+        TextAlign::Left => 0.0,
+        TextAlign::Center => (width - line_width) / 2.0,
+        TextAlign::Right => width - line_width,
+    }
+}
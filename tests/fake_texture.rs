@@ -2,6 +2,7 @@ extern crate graphics;
 
 use self::graphics::ImageSize;
 
+#[derive(Default)]
 pub struct FakeTexture;
 
 impl FakeTexture {
@@ -3,11 +3,14 @@ mod fake_texture;
 extern crate sprite;
 extern crate ai_behavior;
 extern crate input;
+extern crate graphics;
 
 use std::rc::Rc;
 use ai_behavior::{Action};
 
+use graphics::math::identity;
 use sprite::*;
+use sprite::testing::{ RecordingGraphics, TestTexture };
 use fake_texture::FakeTexture;
 
 #[test]
@@ -62,6 +65,1039 @@ fn remove_child_when_done_when_animations_paused() {
     assert!(scene.child(id).is_some());
 }
 
+#[test]
+fn shake_action_moves_camera_not_sprite() {
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let mut sprite = Sprite::from_texture(Rc::new(FakeTexture::new()));
+    sprite.set_position(10.0, 20.0);
+
+    let id = scene.add_child(sprite);
+    scene.run(id, &Action(Shake(1.0)));
+    scene.event(&dt_event(0.1));
+
+    assert_eq!(0, scene.running_for_child(id).unwrap());
+    assert!(scene.camera().get_trauma() > 0.0);
+    assert_eq!((10.0, 20.0), scene.child(id).unwrap().get_position());
+
+    scene.event(&dt_event(2.0));
+    assert_eq!(0.0, scene.camera().get_trauma());
+    assert!(!scene.camera().is_active());
+
+    // wrapped camera effects still reach the camera
+    scene.run(id, &Action(Ease(EaseFunction::QuadraticIn, Box::new(Shake(1.0)))));
+    scene.event(&dt_event(0.1));
+    assert_eq!(0, scene.running_for_child(id).unwrap());
+    assert!(scene.camera().get_trauma() > 0.0);
+}
+
+#[test]
+fn layers_keep_draw_order_and_membership() {
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    scene.add_layer("ui", 10).set_screen_space(true);
+    scene.add_layer("background", -10);
+
+    let names: Vec<&str> = scene.layers().iter().map(|l| l.get_name()).collect();
+    assert_eq!(vec!["background", DEFAULT_LAYER, "ui"], names);
+
+    let tex = Rc::new(FakeTexture::new());
+    let hud = scene.add_child_to_layer("ui", Sprite::from_texture(tex.clone()));
+    let hero = scene.add_child(Sprite::from_texture(tex.clone()));
+    assert_eq!(Some("ui"), scene.child_layer(hud));
+    assert_eq!(Some(DEFAULT_LAYER), scene.child_layer(hero));
+
+    assert!(scene.set_child_layer(hero, "background"));
+    assert_eq!(&[hero][..], scene.layer("background").unwrap().children());
+    assert!(scene.layer(DEFAULT_LAYER).unwrap().children().is_empty());
+
+    let removed = scene.remove_layer("ui").unwrap();
+    assert_eq!(1, removed.len());
+    assert!(scene.child(hud).is_none());
+    assert!(scene.remove_layer(DEFAULT_LAYER).is_none());
+}
+
+#[test]
+fn sprite_batch_groups_by_texture_keeping_overlaps_in_order() {
+    let a = FakeTexture::new();
+    let b = FakeTexture::new();
+    let quad = |texture, x| Quad {
+        texture,
+        transform: identity(),
+        rect: [x, 0.0, 32.0, 32.0],
+        src_rect: [0.0, 0.0, 32.0, 32.0],
+        color: [1.0; 4],
+    };
+
+    // a, b, a side by side: the second `a` joins the first group
+    let mut batch = SpriteBatch::new();
+    batch.push(quad(&a, 0.0));
+    batch.push(quad(&b, 40.0));
+    batch.push(quad(&a, 80.0));
+    assert_eq!(3, batch.len());
+    assert_eq!(2, batch.groups());
+
+    // the second `a` covers `b`, so it must stay on top of it
+    batch.clear();
+    batch.push(quad(&a, 0.0));
+    batch.push(quad(&b, 40.0));
+    batch.push(quad(&a, 50.0));
+    assert_eq!(3, batch.groups());
+}
+
+#[test]
+fn draw_records_sprite_quads_in_layer_order() {
+    let mut scene: Scene<TestTexture> = Scene::new();
+    scene.add_layer("background", -1);
+
+    let mut hero = Sprite::from_texture(Rc::new(TestTexture::new(1, 32, 16)));
+    hero.set_position(100.0, 50.0);
+    scene.add_child(hero);
+    scene.add_child_to_layer("background", Sprite::from_texture(Rc::new(TestTexture::new(2, 8, 8))));
+
+    let mut g = RecordingGraphics::new();
+    scene.draw(identity(), &mut g);
+
+    g.assert_textures(&[2, 1]);
+    g.assert_quad_bounds(0, [-4.0, -4.0, 8.0, 8.0], 1e-4);
+    g.assert_quad_bounds(1, [84.0, 42.0, 32.0, 16.0], 1e-4);
+    g.assert_quad_color(1, [1.0; 4], 0.0);
+}
+
+#[test]
+fn parallax_layers_scroll_with_a_fraction_of_the_camera() {
+    let mut scene: Scene<TestTexture> = Scene::new();
+    scene.add_layer("far", -1).set_parallax(0.5, 0.0);
+    scene.add_layer("ui", 1).set_screen_space(true);
+
+    let tex = Rc::new(TestTexture::new(1, 2, 2));
+    scene.add_child_to_layer("far", Sprite::from_texture(tex.clone()));
+    scene.add_child(Sprite::from_texture(tex.clone()));
+    scene.add_child_to_layer("ui", Sprite::from_texture(tex.clone()));
+    scene.camera_mut().set_position(100.0, 20.0);
+
+    let mut g = RecordingGraphics::new();
+    scene.draw(identity(), &mut g);
+
+    let centers: Vec<[f32; 2]> = g.quads().iter().map(|q| q.center()).collect();
+    assert_eq!(vec![[-50.0, 0.0], [-100.0, -20.0], [0.0, 0.0]], centers);
+}
+
+#[test]
+fn wrapping_layers_repeat_to_cover_the_viewport() {
+    let mut scene: Scene<TestTexture> = Scene::new();
+    scene.add_layer("tiles", -1).set_wrap(Some(100.0), None);
+    let mut tile = Sprite::from_texture(Rc::new(TestTexture::new(1, 100, 10)));
+    tile.set_position(50.0, 0.0);
+    scene.add_child_to_layer("tiles", tile);
+    scene.camera_mut().set_viewport_size(250.0, 100.0);
+    scene.camera_mut().set_position(130.0, 0.0);
+
+    let mut g = RecordingGraphics::new();
+    scene.draw(identity(), &mut g);
+
+    let centers: Vec<[f32; 2]> = g.quads().iter().map(|q| q.center()).collect();
+    assert_eq!(vec![[20.0, 0.0], [120.0, 0.0], [220.0, 0.0], [320.0, 0.0]], centers);
+}
+
+#[test]
+fn draw_batched_matches_draw_with_fewer_calls() {
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let a = Rc::new(TestTexture::new(1, 10, 10));
+    let b = Rc::new(TestTexture::new(2, 10, 10));
+    for i in 0..4 {
+        let mut sprite = Sprite::from_texture(if i % 2 == 0 { a.clone() } else { b.clone() });
+        sprite.set_position(i as f64 * 20.0, 0.0);
+        scene.add_child(sprite);
+    }
+
+    let mut plain = RecordingGraphics::new();
+    scene.draw(identity(), &mut plain);
+    let mut batched = RecordingGraphics::new();
+    scene.draw_batched(identity(), &mut batched);
+
+    assert_eq!(4, plain.calls().len());
+    assert_eq!(2, batched.calls().len());
+    batched.assert_textures(&[1, 1, 2, 2]);
+
+    let mut expected = plain.quads();
+    expected.sort_by_key(|q| q.texture);
+    assert_eq!(expected, batched.quads());
+}
+
+#[test]
+fn debug_overlay_draws_selected_sprites_only() {
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let tex = Rc::new(TestTexture::new(1, 10, 10));
+    let mut parent = Sprite::from_texture(tex.clone());
+    parent.add_child(Sprite::from_texture(tex.clone()));
+    parent.set_debug(true);
+    scene.add_child(parent);
+
+    // 4 bound lines, 1 link and 1 anchor marker for the parent
+    let mut g = RecordingGraphics::new();
+    scene.draw_debug(identity(), &mut g);
+    assert_eq!(6, g.calls().len());
+    g.assert_quad_count(0);
+
+    // the child has no children to link to
+    scene.debug_draw_mut().all = true;
+    g.clear();
+    scene.draw_debug(identity(), &mut g);
+    assert_eq!(11, g.calls().len());
+}
+
+#[test]
+fn tint_and_hue_shift_tween_the_color() {
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let mut sprite = Sprite::from_texture(Rc::new(FakeTexture::new()));
+    sprite.set_color(1.0, 0.0, 0.0);
+    let id = scene.add_child(sprite);
+
+    scene.run(id, &Action(Ease(EaseFunction::QuadraticIn, Box::new(TintTo(1.0, 0.0, 0.0, 1.0)))));
+    scene.event(&dt_event(0.5));
+    let (r, _, b) = scene.child(id).unwrap().get_color();
+    assert!((r - 0.75).abs() < 1e-6 && (b - 0.25).abs() < 1e-6);
+    scene.event(&dt_event(0.5));
+    assert_eq!((0.0, 0.0, 1.0), scene.child(id).unwrap().get_color());
+
+    // blue shifted by 120 degrees is red
+    scene.run(id, &Action(HueShift(1.0, 120.0)));
+    scene.event(&dt_event(1.0));
+    let (r, g, b) = scene.child(id).unwrap().get_color();
+    assert!((r - 1.0).abs() < 1e-6 && g.abs() < 1e-6 && b.abs() < 1e-6);
+}
+
+// Every character is 10 wide and drawn from a 8x10 cell of texture 9
+struct FakeGlyphs {
+    texture: TestTexture,
+}
+
+impl graphics::character::CharacterCache for FakeGlyphs {
+    type Texture = TestTexture;
+    type Error = ();
+
+    fn character(&mut self, _size: u32, _ch: char)
+        -> Result<graphics::character::Character<'_, TestTexture>, ()>
+    {
+        Ok(graphics::character::Character {
+            offset: [0.0, 10.0],
+            advance_size: [10.0, 0.0],
+            atlas_offset: [0.0, 0.0],
+            atlas_size: [8.0, 10.0],
+            texture: &self.texture,
+            is_invalid: false,
+        })
+    }
+}
+
+#[test]
+fn text_labels_wrap_align_and_tint() {
+    use std::cell::RefCell;
+
+    let glyphs = Rc::new(RefCell::new(FakeGlyphs { texture: TestTexture::new(9, 64, 64) }));
+    let mut label = Label::new(glyphs.clone(), "ab", 10);
+    assert_eq!((20.0, 12.0), label.size());
+    label.set_text("ab cde");
+    label.set_wrap_width(Some(35.0));
+    label.set_line_spacing(2.0);
+    label.set_align(TextAlign::Right);
+    assert_eq!((30.0, 40.0), label.size());
+    // the layout is kept while the character cache is borrowed
+    {
+        let _borrowed = glyphs.borrow_mut();
+        assert_eq!((30.0, 40.0), label.size());
+    }
+
+    let mut sprite = Sprite::from_text(label);
+    sprite.set_anchor(0.0, 0.0);
+    sprite.set_position(100.0, 0.0);
+    sprite.set_rgba(1.0, 0.0, 0.0, 0.5);
+    let mut scene: Scene<TestTexture> = Scene::new();
+    scene.add_child(sprite);
+
+    let mut g = RecordingGraphics::new();
+    scene.draw(identity(), &mut g);
+
+    g.assert_textures(&[9; 5]);
+    // "ab" is right aligned with "cde"
+    g.assert_quad_bounds(0, [110.0, 0.0, 8.0, 10.0], 1e-4);
+    g.assert_quad_bounds(2, [100.0, 20.0, 8.0, 10.0], 1e-4);
+    g.assert_quad_color(4, [1.0, 0.0, 0.0, 0.5], 0.0);
+}
+
+#[test]
+fn bitmap_fonts_reject_malformed_ids() {
+    let common = "common lineHeight=16 base=12\n";
+    for line in &[
+        "page id=-1 file=\"a.png\"",
+        "page id=1.5 file=\"a.png\"",
+        "page id=4000000000 file=\"a.png\"",
+        "char id=-1 x=0 y=0 width=8 height=10 xoffset=0 yoffset=0 xadvance=9",
+        "char id=97 x=0 y=0 width=8 height=10 xoffset=0 yoffset=0 xadvance=9 page=-1",
+        "kerning first=97.5 second=98 amount=-2",
+    ] {
+        let error = BitmapFont::parse(&format!("{}{}", common, line)).unwrap_err();
+        assert_eq!(2, error.line, "{}", line);
+    }
+}
+
+#[test]
+fn bitmap_text_kerns_glyphs_and_reveals_them() {
+    let source = "info face=\"Test Font\" size=16\n\
+                  common lineHeight=16 base=12 scaleW=64 scaleH=64 pages=1\n\
+                  page id=0 file=\"test 0.png\"\n\
+                  chars count=2\n\
+                  char id=97 x=0 y=0 width=8 height=10 xoffset=1 yoffset=2 xadvance=9 page=0\n\
+                  char id=98 x=8 y=0 width=8 height=10 xoffset=0 yoffset=2 xadvance=9 page=0\n\
+                  kerning first=97 second=98 amount=-2\n";
+    let font = Rc::new(BitmapFont::parse(source).unwrap());
+    assert_eq!(&["test 0.png".to_owned()][..], font.pages());
+    assert_eq!(-2.0, font.kerning('a', 'b'));
+    assert!(BitmapFont::parse("char id=97").is_err());
+
+    let mut text = BitmapText::new(font, vec![Rc::new(TestTexture::new(3, 64, 64))], "ab b");
+    assert_eq!((4, 25.0, 16.0), (text.char_count(), text.size().0, text.size().1));
+    text.set_text("ab\nb");
+    assert_eq!((16.0, 32.0), text.size());
+    let mut sprite = Sprite::from_bitmap_text(text);
+    sprite.set_anchor(0.0, 0.0);
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let id = scene.add_child(sprite);
+
+    let mut g = RecordingGraphics::new();
+    scene.draw(identity(), &mut g);
+    g.assert_textures(&[3; 3]);
+    g.assert_quad_bounds(0, [1.0, 2.0, 8.0, 10.0], 1e-4);
+    g.assert_quad_bounds(1, [7.0, 2.0, 8.0, 10.0], 1e-4);
+    g.assert_quad_bounds(2, [0.0, 18.0, 8.0, 10.0], 1e-4);
+
+    // typewriter reveal
+    scene.run(id, &Action(RevealText(1.0)));
+    scene.event(&dt_event(0.5));
+    g.clear();
+    scene.draw(identity(), &mut g);
+    g.assert_quad_count(1);
+
+    scene.event(&dt_event(0.6));
+    g.clear();
+    scene.draw(identity(), &mut g);
+    g.assert_quad_count(3);
+
+    // the wave moves glyphs vertically over time
+    if let Content::BitmapText(ref mut text) = *scene.child_mut(id).unwrap().get_content_mut() {
+        text.set_effect(Some(wave(4.0, 4.0, 0.125)));
+    }
+    scene.event(&dt_event(0.9));
+    g.clear();
+    scene.draw(identity(), &mut g);
+    g.assert_quad_bounds(0, [1.0, 6.0, 8.0, 10.0], 1e-4);
+}
+
+#[test]
+fn shape_sprites_draw_untextured_and_animate() {
+    let mut sprite = Sprite::from_shape(Shape::rectangle(20.0, 10.0));
+    sprite.set_anchor(0.0, 0.0);
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let id = scene.add_child(sprite);
+    let outline = Shape::new(Geometry::Polygon(vec![[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]]),
+                             ShapeStyle::Stroke(2.0));
+    scene.child_mut(id).unwrap().add_child(Sprite::from_shape(outline));
+
+    scene.run(id, &Action(MoveTo(1.0, 10.0, 10.0)));
+    scene.run(id, &Action(TintTo(1.0, 1.0, 0.0, 0.0)));
+    scene.event(&dt_event(1.0));
+
+    let mut g = RecordingGraphics::new();
+    scene.draw(identity(), &mut g);
+    g.assert_quad_count(0);
+    // the rectangle, then one call per side of the triangle
+    assert_eq!(4, g.calls().len());
+    let rect = &g.calls()[0];
+    assert_eq!(6, rect.vertices.len());
+    assert_eq!([1.0, 0.0, 0.0, 1.0], rect.colors[0]);
+    let min_x = rect.vertices.iter().map(|v| v[0]).fold(f32::MAX, f32::min);
+    let max_y = rect.vertices.iter().map(|v| v[1]).fold(f32::MIN, f32::max);
+    assert_eq!((10.0, 20.0), (min_x, max_y));
+
+    let triangles = g.untextured_triangles();
+    g.clear();
+    scene.draw_batched(identity(), &mut g);
+    assert_eq!(triangles, g.untextured_triangles());
+}
+
+#[test]
+fn emitters_spawn_move_and_fade_particles() {
+    let settings = ParticleSettings {
+        rate: 10.0,
+        speed: [100.0, 100.0],
+        angle: 0.0,
+        ..ParticleSettings::default()
+    };
+    let mut emitter = Emitter::new(Rc::new(TestTexture::new(1, 4, 4)), settings);
+    emitter.set_seed(7);
+    let mut sprite = Sprite::from_emitter(emitter);
+    sprite.set_position(50.0, 50.0);
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let id = scene.add_child(sprite);
+
+    scene.run(id, &Action(EmitBurst(3)));
+    scene.event(&dt_event(0.5));
+    let mut g = RecordingGraphics::new();
+    scene.draw(identity(), &mut g);
+    g.assert_textures(&[1; 8]);
+    g.assert_quad_bounds(0, [48.0, 48.0, 4.0, 4.0], 1e-4);
+
+    scene.event(&dt_event(0.5));
+    g.clear();
+    scene.draw(identity(), &mut g);
+    g.assert_quad_count(13);
+    g.assert_quad_bounds(0, [98.0, 48.0, 4.0, 4.0], 1e-4);
+    g.assert_quad_color(0, [1.0, 1.0, 1.0, 0.5], 1e-4);
+
+    scene.event(&dt_event(0.6));
+    match *scene.child(id).unwrap().get_content() {
+        Content::Emitter(ref emitter) => {
+            assert!(emitter.particles().iter().all(|p| p.age < 1.0));
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn clip_masks_use_the_stencil_buffer_and_nest() {
+    use graphics::draw_state::Stencil;
+
+    let texture = Rc::new(TestTexture::new(1, 100, 100));
+    let mut panel = Sprite::from_texture(texture.clone());
+    panel.set_position(50.0, 50.0);
+    panel.set_clip(Some(Clip::Rect([0.0, 0.0, 50.0, 100.0])));
+    let mut inner = Sprite::from_texture(texture.clone());
+    inner.set_clip(Some(Clip::Rect([0.0, 0.0, 100.0, 10.0])));
+    inner.add_child(Sprite::from_texture(texture.clone()));
+    panel.add_child(inner);
+    panel.add_child(Sprite::from_texture(texture.clone()));
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let id = scene.add_child(panel);
+
+    let mut g = RecordingGraphics::new();
+    scene.draw(identity(), &mut g);
+    let stencils: Vec<_> = g.calls().iter().map(|call| call.draw_state.stencil).collect();
+    assert_eq!(vec![
+        None,                       // panel
+        Some(Stencil::Increment),   // panel mask
+        Some(Stencil::Inside(1)),   // inner
+        Some(Stencil::Increment),   // inner mask
+        Some(Stencil::Inside(2)),   // inner child
+        Some(Stencil::Increment),   // panel mask again
+        Some(Stencil::Inside(1)),   // last child
+    ], stencils);
+    assert_eq!(2, g.stencil_clears());
+    let mask = &g.calls()[1];
+    let max_x = mask.vertices.iter().map(|v| v[0]).fold(f32::MIN, f32::max);
+    assert_eq!(50.0, max_x);
+
+    // the mask rectangle is animated
+    scene.run(id, &Action(ClipTo(1.0, 0.0, 0.0, 100.0, 100.0)));
+    scene.event(&dt_event(0.5));
+    assert_eq!(Some(Clip::Rect([0.0, 0.0, 75.0, 100.0])), scene.child(id).unwrap().get_clip());
+}
+
+#[test]
+fn colliders_overlap_in_world_space_filtered_by_layers() {
+    let texture = Rc::new(TestTexture::new(1, 10, 10));
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let square = Collider::new(CollisionShape::Obb([0.0, 0.0, 10.0, 10.0]));
+
+    let mut a = Sprite::from_texture(texture.clone());
+    a.set_collider(Some(square.clone()));
+    let a = scene.add_child(a);
+
+    // only overlaps `a` because it is rotated
+    let mut b = Sprite::from_texture(texture.clone());
+    b.set_position(12.0, 0.0);
+    b.set_rotation(45.0);
+    let mut collider = square.clone();
+    collider.set_layers(1);
+    b.set_collider(Some(collider));
+    let b = scene.add_child(b);
+
+    // a circle scaled by its parent, on a layer `b` does not collide with
+    let mut parent = Sprite::from_texture(texture.clone());
+    parent.set_position(25.0, 0.0);
+    parent.set_scale(2.0, 2.0);
+    let mut c = Sprite::from_texture(texture.clone());
+    let mut collider = Collider::new(CollisionShape::Circle([5.0, 5.0], 4.0));
+    collider.set_layers(2);
+    collider.set_mask(2);
+    c.set_collider(Some(collider));
+    let c = parent.add_child(c);
+    scene.add_child(parent);
+
+    assert!(scene.collisions().is_empty());
+    scene.event(&dt_event(0.0));
+    assert_eq!(&[(a, b)][..], scene.collisions());
+    assert!(scene.overlaps(b, c));
+    assert!(!scene.overlaps(a, c));
+
+    scene.child_mut(b).unwrap().set_rotation(0.0);
+    assert!(!scene.overlaps(a, b));
+}
+
+#[test]
+fn velocity_is_integrated_on_update() {
+    use ai_behavior::Sequence;
+
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let id = scene.add_child(Sprite::from_texture(Rc::new(FakeTexture::new())));
+    scene.run(id, &Sequence(vec![
+        Action(SetVelocity(10.0, 0.0)),
+        Action(Impulse(0.0, 20.0)),
+        Action(SetAngularVelocity(90.0)),
+    ]));
+    scene.event(&dt_event(0.0));
+    scene.event(&dt_event(1.0));
+    {
+        let sprite = scene.child(id).unwrap();
+        assert_eq!((10.0, 20.0), sprite.get_position());
+        assert_eq!(90.0, sprite.get_rotation());
+    }
+
+    // drag halves the velocity every second, acceleration adds to it
+    let sprite = scene.child_mut(id).unwrap();
+    sprite.set_velocity(8.0, 0.0);
+    sprite.set_drag(2f64.ln());
+    sprite.update(1.0);
+    assert!((sprite.get_velocity().0 - 4.0).abs() < 1e-9);
+    sprite.set_drag(0.0);
+    sprite.set_acceleration(0.0, 2.0);
+    sprite.update(0.5);
+    assert_eq!((4.0, 1.0), sprite.get_velocity());
+}
+
+#[test]
+fn pointer_events_hit_test_bubble_and_stop_when_consumed() {
+    use std::cell::RefCell;
+
+    let texture = Rc::new(TestTexture::new(1, 100, 100));
+    let mut button = Sprite::from_texture(texture);
+    button.set_position(50.0, 50.0);
+    let icon = button.add_child(Sprite::from_texture(Rc::new(TestTexture::new(2, 20, 20))));
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let button = scene.add_child(button);
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let icon_log = log.clone();
+    scene.on_pointer(icon, move |e| {
+        icon_log.borrow_mut().push((e.kind, e.current, e.local_position));
+        if let PointerEventKind::Click(_) = e.kind {
+            e.consume();
+        }
+    });
+    let button_log = log.clone();
+    scene.on_pointer(button, move |e| button_log.borrow_mut().push((e.kind, e.current, e.local_position)));
+    let kinds = || log.borrow_mut().drain(..).map(|(kind, id, _)| (kind, id)).collect::<Vec<_>>();
+
+    scene.event(&cursor_event(55.0, 50.0));
+    assert_eq!([5.0, 0.0], log.borrow()[0].2);
+    assert_eq!(vec![
+        (PointerEventKind::Enter, button),
+        (PointerEventKind::Enter, icon),
+        (PointerEventKind::Move, icon),
+        (PointerEventKind::Move, button),
+    ], kinds());
+
+    let left = input::MouseButton::Left;
+    scene.event(&button_event(input::ButtonState::Press));
+    scene.event(&button_event(input::ButtonState::Release));
+    assert_eq!(vec![
+        (PointerEventKind::Press(left), icon),
+        (PointerEventKind::Press(left), button),
+        (PointerEventKind::Release(left), icon),
+        (PointerEventKind::Release(left), button),
+        (PointerEventKind::Click(left), icon),
+    ], kinds());
+
+    scene.event(&cursor_event(10.0, 10.0));
+    assert_eq!(vec![
+        (PointerEventKind::Leave, icon),
+        (PointerEventKind::Move, button),
+    ], kinds());
+    assert_eq!(Some(button), scene.hit_test([10.0, 10.0]));
+    assert_eq!(None, scene.hit_test([110.0, 10.0]));
+}
+
+#[test]
+fn hit_tests_ignore_children_clipped_away() {
+    let mut parent = Sprite::from_texture(Rc::new(TestTexture::new(1, 50, 50)));
+    parent.set_anchor(0.0, 0.0);
+    parent.set_clip(Some(Clip::Rect([0.0, 0.0, 50.0, 50.0])));
+    let mut child = Sprite::from_texture(Rc::new(TestTexture::new(2, 400, 400)));
+    child.set_anchor(0.0, 0.0);
+    child.set_position(20.0, 20.0);
+    let child = parent.add_child(child);
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let parent = scene.add_child(parent);
+
+    assert_eq!(Some(child), scene.hit_test([30.0, 30.0]));
+    assert_eq!(None, scene.hit_test([300.0, 300.0]));
+
+    scene.child_mut(parent).unwrap().set_clip(Some(Clip::Scissor([0, 0, 25, 25])));
+    assert_eq!(Some(child), scene.hit_test([24.0, 24.0]));
+    assert_eq!(Some(parent), scene.hit_test([30.0, 30.0]));
+}
+
+#[test]
+fn touches_are_ignored_until_the_viewport_size_is_set() {
+    use std::cell::RefCell;
+    use input::{ Input, Touch, TouchArgs };
+
+    let mut sprite = Sprite::from_texture(Rc::new(TestTexture::new(1, 20, 20)));
+    sprite.set_position(100.0, 50.0);
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let id = scene.add_child(sprite);
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let handler_log = log.clone();
+    scene.on_pointer(id, move |e| handler_log.borrow_mut().push(e.kind));
+
+    let touch: input::Event = Input::Move(input::Motion::Touch(
+        TouchArgs::new(0, 0, [0.5, 0.25], 1.0, Touch::Start))).into();
+    scene.event(&touch);
+    assert!(log.borrow().is_empty());
+
+    scene.camera_mut().set_viewport_size(200.0, 200.0);
+    scene.event(&touch);
+    assert_eq!(Some(&PointerEventKind::Press(input::MouseButton::Left)), log.borrow().last());
+}
+
+#[test]
+fn dragged_sprites_follow_the_pointer_in_parent_space_and_drop() {
+    use std::cell::RefCell;
+
+    let texture = Rc::new(TestTexture::new(1, 100, 100));
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let mut target = Sprite::from_texture(texture.clone());
+    target.set_position(300.0, 100.0);
+    target.set_drop_target(true);
+    let target = scene.add_child(target);
+
+    let mut board = Sprite::from_texture(texture.clone());
+    board.set_position(100.0, 100.0);
+    board.set_rotation(90.0);
+    board.set_scale(2.0, 2.0);
+    let mut piece = Sprite::from_texture(Rc::new(TestTexture::new(2, 10, 10)));
+    piece.set_draggable(true);
+    let piece = board.add_child(piece);
+    scene.add_child(board);
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    for &id in &[piece, target] {
+        let log = log.clone();
+        scene.on_pointer(id, move |e| match e.kind {
+            PointerEventKind::DragStart | PointerEventKind::DragMove |
+            PointerEventKind::DragEnd | PointerEventKind::Drop |
+            PointerEventKind::Click(_) => log.borrow_mut().push((e.kind, e.current, e.related)),
+            _ => {},
+        });
+    }
+
+    scene.event(&cursor_event(100.0, 100.0));
+    scene.event(&button_event(input::ButtonState::Press));
+    scene.event(&cursor_event(100.0, 120.0));
+    let (x, y) = scene.child(piece).unwrap().get_position();
+    assert!((x - 10.0).abs() < 1e-9 && y.abs() < 1e-9);
+
+    scene.event(&cursor_event(300.0, 100.0));
+    scene.event(&button_event(input::ButtonState::Release));
+    let (x, y) = scene.child(piece).unwrap().get_position();
+    assert!(x.abs() < 1e-9 && (y + 100.0).abs() < 1e-9);
+    assert_eq!(vec![
+        (PointerEventKind::DragStart, piece, None),
+        (PointerEventKind::DragMove, piece, None),
+        (PointerEventKind::DragMove, piece, None),
+        (PointerEventKind::DragEnd, piece, Some(target)),
+        (PointerEventKind::Drop, target, Some(piece)),
+    ], *log.borrow());
+
+    // constrained to its parent's y axis
+    scene.child_mut(piece).unwrap().set_drag_axis(DragAxis::Vertical);
+    scene.event(&cursor_event(300.0, 100.0));
+    scene.event(&button_event(input::ButtonState::Press));
+    scene.event(&cursor_event(310.0, 140.0));
+    let (x, y) = scene.child(piece).unwrap().get_position();
+    assert!(x.abs() < 1e-9 && (y + 105.0).abs() < 1e-9);
+}
+
+#[test]
+fn drags_start_past_a_threshold_and_follow_the_camera() {
+    use std::cell::RefCell;
+
+    let mut sprite = Sprite::from_texture(Rc::new(TestTexture::new(1, 20, 20)));
+    sprite.set_position(50.0, 50.0);
+    sprite.set_draggable(true);
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let id = scene.add_child(sprite);
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let handler_log = log.clone();
+    scene.on_pointer(id, move |e| match e.kind {
+        PointerEventKind::DragStart | PointerEventKind::Click(_) => {
+            handler_log.borrow_mut().push(e.kind)
+        },
+        _ => {},
+    });
+
+    // jitter under the threshold is still a click
+    scene.event(&cursor_event(50.0, 50.0));
+    scene.event(&button_event(input::ButtonState::Press));
+    scene.event(&cursor_event(52.0, 51.0));
+    scene.event(&button_event(input::ButtonState::Release));
+    assert_eq!(vec![PointerEventKind::Click(input::MouseButton::Left)], *log.borrow());
+    assert_eq!((50.0, 50.0), scene.child(id).unwrap().get_position());
+
+    scene.event(&button_event(input::ButtonState::Press));
+    scene.event(&cursor_event(62.0, 51.0));
+    assert_eq!(PointerEventKind::DragStart, log.borrow()[1]);
+    assert_eq!((60.0, 50.0), scene.child(id).unwrap().get_position());
+
+    // the sprite stays under the pointer when the camera moves
+    scene.camera_mut().set_position(100.0, 0.0);
+    scene.event(&cursor_event(62.0, 51.0));
+    assert_eq!((160.0, 50.0), scene.child(id).unwrap().get_position());
+    assert_eq!(Some(id), scene.hit_test([62.0, 51.0]));
+}
+
+#[test]
+fn finished_animations_are_reported() {
+    use std::cell::Cell;
+    use ai_behavior::{Fail, Failure, Success};
+
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let id = scene.add_child(Sprite::from_texture(Rc::new(FakeTexture::new())));
+    let fade = Action(FadeOut(1.0));
+    let fail = Fail(Box::new(Action(Show)));
+    scene.run(id, &fade);
+    scene.run(id, &fail);
+
+    scene.event(&dt_event(0.5));
+    let events: Vec<_> = scene.drain_events().collect();
+    assert_eq!(1, events.len());
+    assert!(events[0].sprite == id && events[0].behavior == fail && events[0].status == Failure);
+    assert_eq!(0, scene.drain_events().count());
+
+    let finished = Rc::new(Cell::new(0));
+    let counter = finished.clone();
+    scene.on_animation_finished(Some(Box::new(move |e: &AnimationEvent| {
+        assert!(e.behavior == fade && e.status == Success);
+        counter.set(counter.get() + 1);
+    })));
+    scene.event(&dt_event(0.6));
+    assert_eq!(1, finished.get());
+    assert_eq!(0, scene.drain_events().count());
+}
+
+#[test]
+fn animation_handles_control_one_run_of_a_behavior() {
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let id = scene.add_child(Sprite::from_texture(Rc::new(FakeTexture::new())));
+    let move_by = Action(MoveBy(1.0, 10.0, 0.0));
+    let first = scene.run(id, &move_by);
+    let second = scene.run(id, &move_by);
+    assert!(first != second);
+
+    scene.pause_animation(second);
+    scene.event(&dt_event(0.5));
+    assert_eq!(5.0, scene.child(id).unwrap().get_position().0);
+    assert!(scene.is_running(second));
+
+    scene.stop_animation(first);
+    scene.resume_animation(second);
+    assert!(!scene.is_running(first));
+    scene.event(&dt_event(1.0));
+    assert_eq!(15.0, scene.child(id).unwrap().get_position().0);
+    assert!(!scene.is_running(second));
+    assert_eq!(second, scene.drain_events().next_back().unwrap().id);
+    assert_eq!(Some(0), scene.running_for_child(id));
+}
+
+#[test]
+fn time_scales_multiply_down_the_tree() {
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let mut parent = Sprite::from_texture(Rc::new(FakeTexture::new()));
+    let child = Sprite::from_texture(Rc::new(FakeTexture::new()));
+    let child_id = parent.add_child(child);
+    parent.set_time_scale(0.5);
+    parent.set_velocity(10.0, 0.0);
+    let parent_id = scene.add_child(parent);
+    let ui = scene.add_child(Sprite::from_texture(Rc::new(FakeTexture::new())));
+    scene.run(child_id, &Action(MoveBy(1.0, 10.0, 0.0)));
+    scene.run(ui, &Action(MoveBy(1.0, 10.0, 0.0)));
+
+    scene.set_time_scale(0.5);
+    scene.event(&dt_event(1.0));
+    assert_eq!(2.5, scene.child(parent_id).unwrap().get_position().0);
+    assert_eq!(2.5, scene.child(child_id).unwrap().get_position().0);
+    assert_eq!(5.0, scene.child(ui).unwrap().get_position().0);
+
+    // pause the world but keep the ui going
+    scene.set_time_scale(1.0);
+    scene.child_mut(parent_id).unwrap().set_time_scale(0.0);
+    scene.event(&dt_event(0.5));
+    assert_eq!(2.5, scene.child(child_id).unwrap().get_position().0);
+    assert_eq!(10.0, scene.child(ui).unwrap().get_position().0);
+
+    // the camera settles during a hit-stop
+    scene.set_time_scale(0.0);
+    scene.camera_mut().add_trauma(1.0);
+    scene.camera_mut().zoom_punch(0.5, 0.2);
+    scene.event(&dt_event(1.0));
+    assert_eq!(0.0, scene.camera().get_trauma());
+    assert_eq!(1.0, scene.camera().get_zoom());
+    assert_eq!(10.0, scene.child(ui).unwrap().get_position().0);
+}
+
+#[test]
+fn subtrees_pause_and_resume_together() {
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let mut parent = Sprite::from_texture(Rc::new(FakeTexture::new()));
+    let child_id = parent.add_child(Sprite::from_texture(Rc::new(FakeTexture::new())));
+    let parent_id = scene.add_child(parent);
+    let other = scene.add_child(Sprite::from_texture(Rc::new(FakeTexture::new())));
+    let a = scene.run(parent_id, &Action(MoveBy(1.0, 10.0, 0.0)));
+    let b = scene.run(child_id, &Action(MoveBy(1.0, 10.0, 0.0)));
+    let c = scene.run(other, &Action(MoveBy(1.0, 10.0, 0.0)));
+
+    scene.pause_subtree(parent_id);
+    assert!(scene.is_paused(a) && scene.is_paused(b) && !scene.is_paused(c));
+    scene.event(&dt_event(0.5));
+    assert_eq!(0.0, scene.child(child_id).unwrap().get_position().0);
+    assert_eq!(5.0, scene.child(other).unwrap().get_position().0);
+
+    scene.resume_subtree(parent_id);
+    scene.pause_all();
+    assert!(scene.is_paused(a) && scene.is_paused(b) && scene.is_paused(c));
+    scene.resume_all();
+    assert!(!scene.is_paused(a) && !scene.is_paused(b) && !scene.is_paused(c));
+}
+
+#[test]
+fn animations_report_progress_and_seek() {
+    use ai_behavior::{Sequence, Wait, WaitForever};
+
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let id = scene.add_child(Sprite::from_texture(Rc::new(FakeTexture::new())));
+    let handle = scene.run(id, &Sequence(vec![
+        Action(MoveBy(1.0, 10.0, 0.0)),
+        Wait(1.0),
+        Action(FadeOut(2.0)),
+    ]));
+    let forever = scene.run(id, &WaitForever);
+
+    scene.event(&dt_event(0.5));
+    assert_eq!(Some(0.125), scene.progress(handle));
+    assert_eq!(None, scene.progress(forever));
+    assert_eq!(Some(0.5), scene.elapsed(forever));
+
+    scene.seek(handle, 3.0);
+    assert_eq!(Some(0.75), scene.progress(handle));
+    assert_eq!(10.0, scene.child(id).unwrap().get_position().0);
+    assert_eq!(0.5, scene.child(id).unwrap().get_opacity());
+
+    // scrubbing back undoes what the animation did
+    scene.seek(handle, 0.5);
+    assert_eq!(5.0, scene.child(id).unwrap().get_position().0);
+    assert_eq!(1.0, scene.child(id).unwrap().get_opacity());
+
+    // reaching the end finishes on the next update
+    scene.seek(handle, 4.0);
+    assert!(scene.is_running(handle));
+    scene.event(&cursor_event(1.0, 1.0));
+    assert!(scene.is_running(handle));
+    scene.event(&dt_event(0.0));
+    assert!(!scene.is_running(handle));
+    assert_eq!(0.0, scene.child(id).unwrap().get_opacity());
+}
+
+#[test]
+fn seeking_only_replays_what_the_animation_changes() {
+    use ai_behavior::{Sequence, Wait};
+
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let id = scene.add_child(Sprite::from_texture(Rc::new(FakeTexture::new())));
+    let moving = scene.run(id, &Action(MoveBy(1.0, 10.0, 0.0)));
+    let fading = scene.run(id, &Action(FadeOut(1.0)));
+    scene.child_mut(id).unwrap().set_rotation(30.0);
+    scene.event(&dt_event(0.5));
+
+    // the other animation and game code keep their changes
+    scene.seek(moving, 0.25);
+    let sprite = scene.child(id).unwrap();
+    assert_eq!(2.5, sprite.get_position().0);
+    assert_eq!(0.5, sprite.get_opacity());
+    assert_eq!(30.0, sprite.get_rotation());
+    scene.seek(fading, 0.75);
+    assert_eq!(2.5, scene.child(id).unwrap().get_position().0);
+    assert_eq!(0.25, scene.child(id).unwrap().get_opacity());
+
+    // every blink passed is replayed
+    let blink = scene.run(id, &Action(Blink(1.0, 2)));
+    for &(t, visible) in &[(0.6, true), (0.3, false), (0.8, false), (0.55, true)] {
+        scene.seek(blink, t);
+        assert_eq!(visible, scene.child(id).unwrap().get_visible(), "{}", t);
+    }
+
+    // the camera is not shaken again
+    let shake = scene.run(id, &Sequence(vec![Action(Shake(0.5)), Wait(1.0)]));
+    scene.event(&dt_event(0.0));
+    assert_eq!(0.5, scene.camera().get_trauma());
+    scene.seek(shake, 0.5);
+    scene.seek(shake, 0.0);
+    assert_eq!(0.5, scene.camera().get_trauma());
+}
+
+#[test]
+fn tweens_play_backward_and_yoyo() {
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let id = scene.add_child(Sprite::from_texture(Rc::new(FakeTexture::new())));
+    scene.run(id, &Action(Reverse(Box::new(MoveBy(1.0, 10.0, 0.0)))));
+    scene.event(&dt_event(0.25));
+    assert_eq!(7.5, scene.child(id).unwrap().get_position().0);
+    scene.event(&dt_event(1.0));
+    assert_eq!(0.0, scene.child(id).unwrap().get_position().0);
+    assert_eq!(Some(0), scene.running_for_child(id));
+
+    let pulse = scene.run(id, &Action(Yoyo(Box::new(ScaleTo(1.0, 2.0, 2.0)), Some(2))));
+    scene.event(&dt_event(1.5));
+    assert_eq!((1.5, 1.5), scene.child(id).unwrap().get_scale());
+    scene.event(&dt_event(1.0));
+    assert_eq!((1.5, 1.5), scene.child(id).unwrap().get_scale());
+    scene.event(&dt_event(2.0));
+    assert_eq!((1.0, 1.0), scene.child(id).unwrap().get_scale());
+    assert!(!scene.is_running(pulse));
+
+    // the ease applies to every leg
+    let eased = Ease(EaseFunction::QuadraticIn, Box::new(Yoyo(Box::new(FadeTo(1.0, 0.0)), None)));
+    let forever = scene.run(id, &Action(eased));
+    scene.event(&dt_event(0.5));
+    assert_eq!(0.75, scene.child(id).unwrap().get_opacity());
+    scene.event(&dt_event(1.0));
+    assert_eq!(0.25, scene.child(id).unwrap().get_opacity());
+    scene.event(&dt_event(10.0));
+    assert!(scene.is_running(forever));
+    assert_eq!(None, scene.progress(forever));
+    scene.stop_animation(forever);
+
+    // playing no times finishes at once
+    let (x, _) = scene.child(id).unwrap().get_position();
+    let never = Yoyo(Box::new(MoveBy(1.0, 10.0, 0.0)), Some(0));
+    assert_eq!(Some(0.0), never.duration());
+    let never = scene.run(id, &Action(Ease(EaseFunction::QuadraticIn, Box::new(never))));
+    scene.event(&dt_event(0.5));
+    assert!(!scene.is_running(never));
+    assert_eq!(x, scene.child(id).unwrap().get_position().0);
+}
+
+#[test]
+fn only_tweens_play_backward_or_yoyo() {
+    let wrapped = |a: Animation| Ease(EaseFunction::QuadraticIn, Box::new(a));
+    assert!(MoveBy(1.0, 10.0, 0.0).is_tween());
+    assert!(wrapped(Reverse(Box::new(FadeOut(1.0)))).is_tween());
+    for animation in &[Blink(1.0, 2), Show, EmitBurst(10), wrapped(Hide), Shake(0.5)] {
+        assert!(!animation.is_tween());
+        assert!(!Yoyo(Box::new(Reverse(Box::new(animation.clone()))), Some(2)).is_tween());
+    }
+}
+
+#[test]
+#[should_panic(expected = "only tweens")]
+fn running_a_blink_yoyo_panics() {
+    use ai_behavior::Sequence;
+
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let id = scene.add_child(Sprite::from_texture(Rc::new(FakeTexture::new())));
+    scene.run(id, &Sequence(vec![Action(Show), Action(Yoyo(Box::new(Blink(1.0, 2)), Some(3)))]));
+}
+
+#[test]
+fn sprites_move_along_paths_at_constant_speed() {
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let mut sprite = Sprite::from_texture(Rc::new(FakeTexture::new()));
+    sprite.set_position(100.0, 100.0);
+    let id = scene.add_child(sprite);
+
+    let mut path = Path::new([0.0, 0.0]).line_to([10.0, 0.0]).line_to([10.0, 10.0]);
+    path.set_auto_rotate(true);
+    assert!((path.length() - 20.0).abs() < 1e-9);
+    scene.run(id, &Action(MoveAlongPath(2.0, path)));
+    scene.event(&dt_event(1.5));
+    let (x, y) = scene.child(id).unwrap().get_position();
+    assert!((x - 110.0).abs() < 1e-9 && (y - 105.0).abs() < 1e-9);
+    assert_eq!(90.0, scene.child(id).unwrap().get_rotation());
+
+    // symmetric curves are half way at their middle
+    let arch = Path::new([0.0, 0.0]).quad_to([5.0, 10.0], [10.0, 0.0]);
+    let (p, direction) = arch.sample(0.5);
+    assert!((p[0] - 5.0).abs() < 1e-6 && (p[1] - 5.0).abs() < 1e-6);
+    assert!(direction.unwrap()[1].abs() < 1e-9);
+
+    scene.event(&dt_event(1.0));
+    let line = Path::new([0.0, 0.0]).line_to([-10.0, 0.0]);
+    scene.run(id, &Action(Ease(EaseFunction::QuadraticIn, Box::new(MoveAlongPath(1.0, line)))));
+    scene.event(&dt_event(0.5));
+    let (x, _) = scene.child(id).unwrap().get_position();
+    assert!((x - 107.5).abs() < 1e-9);
+}
+
+#[test]
+fn splines_pass_through_their_waypoints() {
+    let mut scene: Scene<FakeTexture> = Scene::new();
+    let id = scene.add_child(Sprite::from_texture(Rc::new(FakeTexture::new())));
+    let square = vec![[0.0, 0.0], [100.0, 0.0], [100.0, 100.0], [0.0, 100.0]];
+    scene.run(id, &Action(FollowSpline(4.0, square, true, SplineTiming::PerSegment)));
+    let close = |scene: &Scene<FakeTexture>, x: f64, y: f64| {
+        let p = scene.child(id).unwrap().get_position();
+        (p.0 - x).abs() < 1e-6 && (p.1 - y).abs() < 1e-6
+    };
+    scene.event(&dt_event(1.0));
+    assert!(close(&scene, 100.0, 0.0));
+    scene.event(&dt_event(0.5));
+    assert!(scene.child(id).unwrap().get_position().0 > 100.0);
+    scene.event(&dt_event(2.5));
+    assert!(close(&scene, 0.0, 0.0));
+
+    // uneven waypoints are reached at different times at a constant speed
+    let line = vec![[0.0, 0.0], [10.0, 0.0], [100.0, 0.0]];
+    scene.run(id, &Action(FollowSpline(2.0, line.clone(), false, SplineTiming::PerSegment)));
+    scene.event(&dt_event(1.0));
+    assert!(close(&scene, 10.0, 0.0));
+    scene.event(&dt_event(1.0));
+    scene.run(id, &Action(FollowSpline(2.0, line, false, SplineTiming::ConstantSpeed)));
+    scene.event(&dt_event(1.0));
+    let (x, y) = scene.child(id).unwrap().get_position();
+    assert!((x - 50.0).abs() < 0.5 && y == 0.0);
+}
+
+#[test]
+fn anchors_and_source_rectangles_tween() {
+    let mut scene: Scene<TestTexture> = Scene::new();
+    let id = scene.add_child(Sprite::from_texture(Rc::new(TestTexture::new(1, 32, 16))));
+    scene.run(id, &Action(AnchorTo(1.0, 0.0, 1.0)));
+    // reveal a progress bar from the left
+    scene.run(id, &Action(Ease(EaseFunction::QuadraticIn, Box::new(SrcRectTo(1.0, 0.0, 0.0, 0.0, 16.0)))));
+    scene.event(&dt_event(0.5));
+    let sprite = scene.child(id).unwrap();
+    assert_eq!((0.25, 0.75), sprite.get_anchor());
+    assert_eq!(Some([0.0, 0.0, 24.0, 16.0]), sprite.get_src_rect());
+
+    scene.event(&dt_event(0.5));
+    let sprite = scene.child(id).unwrap();
+    assert_eq!((0.0, 1.0), sprite.get_anchor());
+    assert_eq!(Some([0.0, 0.0, 0.0, 16.0]), sprite.get_src_rect());
+}
+
+#[test]
+#[should_panic(expected = "at least one key")]
+fn curves_need_a_key() {
+    let _ = Curve::<f32>::new(vec![]);
+}
+
 fn dt_event(dt: f64) -> input::Event {
     use input::{Event, UpdateArgs, UpdateEvent};
 
@@ -70,3 +1106,19 @@ fn dt_event(dt: f64) -> input::Event {
 
     event
 }
+
+fn cursor_event(x: f64, y: f64) -> input::Event {
+    use input::{Input, Motion};
+
+    Input::Move(Motion::MouseCursor([x, y])).into()
+}
+
+fn button_event(state: input::ButtonState) -> input::Event {
+    use input::{Button, ButtonArgs, Input, MouseButton};
+
+    Input::Button(ButtonArgs {
+        state,
+        button: Button::Mouse(MouseButton::Left),
+        scancode: None,
+    }).into()
+}